@@ -1,14 +1,20 @@
 use crate::models::{Product, StoreManager};
-use crate::ui::{ResultsTab, SearchTab, StoreTab};
-use crate::utils::FileManager;
+use crate::scraping::WebScraper;
+use crate::ui::{AlertsTab, ResultsTab, SchedulesTab, SearchTab, StoreTab};
+use crate::utils::{FileManager, PriceHistory};
 use eframe::egui;
+use std::sync::Arc;
 
 pub struct ScrapingApp {
     store_manager: StoreManager,
     file_manager: FileManager,
+    price_history: Option<PriceHistory>,
+    auto_save_results: bool,
     search_tab: SearchTab,
     store_tab: StoreTab,
     results_tab: ResultsTab,
+    alerts_tab: AlertsTab,
+    schedules_tab: SchedulesTab,
     current_tab: Tab,
     search_results: Option<Vec<Product>>,
 }
@@ -17,40 +23,85 @@ enum Tab {
     Search,
     Stores,
     Results,
+    Alerts,
+    Schedules,
 }
 
 impl ScrapingApp {
     pub fn new() -> Self {
         let file_manager = FileManager::new();
         let store_manager = file_manager.load_stores();
-        
-        //let app_config = file_manager.load_app_config();
-        
-        // Por:
-        let _app_config = file_manager.load_app_config();
-        
+        let app_config = file_manager.load_app_config();
+        let scraper = Arc::new(WebScraper::with_config(&app_config));
+        let price_history = PriceHistory::open(FileManager::HISTORY_DB)
+            .map_err(|e| eprintln!("{}", e))
+            .ok();
+
         Self {
             store_manager,
             file_manager,
-            search_tab: SearchTab::new(),
-            store_tab: StoreTab::new(),
-            results_tab: ResultsTab::new(),
+            price_history,
+            auto_save_results: app_config.auto_save_results,
+            search_tab: SearchTab::new(Arc::clone(&scraper)),
+            store_tab: StoreTab::new(Arc::clone(&scraper)),
+            results_tab: ResultsTab::new(Arc::clone(&scraper)),
+            alerts_tab: AlertsTab::new(),
+            schedules_tab: SchedulesTab::new(scraper),
             current_tab: Tab::Search,
             search_results: None,
         }
     }
 }
 
+impl ScrapingApp {
+    /// Guarda los últimos resultados y los añade al historial de precios
+    fn save_results(&mut self, products: &[Product]) {
+        if let Err(e) = self.file_manager.save_search_results(products) {
+            eprintln!("{}", e);
+        }
+
+        if let Some(history) = &mut self.price_history
+            && let Err(e) = history.record(self.search_tab.last_query(), products)
+        {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+impl ScrapingApp {
+    /// Las ejecuciones programadas pasan por las alertas y el historial de precios,
+    /// pero no sustituyen los resultados que se están viendo
+    fn handle_scheduled_runs(&mut self, ctx: &egui::Context) {
+        for completed in self.schedules_tab.poll(ctx, &self.store_manager) {
+            self.alerts_tab.evaluate(&completed.products, self.price_history.as_ref());
+            if self.auto_save_results
+                && let Some(history) = &mut self.price_history
+                && let Err(e) = history.record(&completed.query, &completed.products)
+            {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+impl Default for ScrapingApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl eframe::App for ScrapingApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_scheduled_runs(ctx);
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.heading("Sistema de Scraping de Productos");
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("🔄 Guardar").clicked() {
-                        if let Err(e) = self.file_manager.save_stores(&self.store_manager) {
-                            eprintln!("Error al guardar tiendas: {}", e);
-                        }
+                    if ui.button("🔄 Guardar").clicked()
+                        && let Err(e) = self.file_manager.save_stores(&self.store_manager)
+                    {
+                        eprintln!("Error al guardar tiendas: {}", e);
                     }
                 });
             });
@@ -67,6 +118,16 @@ impl eframe::App for ScrapingApp {
                 if ui.selectable_label(matches!(self.current_tab, Tab::Results), "📊 Resultados").clicked() {
                     self.current_tab = Tab::Results;
                 }
+                let alerts_label = match self.alerts_tab.unseen_matches() {
+                    0 => "🔔 Alertas".to_string(),
+                    n => format!("🔔 Alertas ({})", n),
+                };
+                if ui.selectable_label(matches!(self.current_tab, Tab::Alerts), alerts_label).clicked() {
+                    self.current_tab = Tab::Alerts;
+                }
+                if ui.selectable_label(matches!(self.current_tab, Tab::Schedules), "⏰ Programadas").clicked() {
+                    self.current_tab = Tab::Schedules;
+                }
             });
         });
         
@@ -74,7 +135,13 @@ impl eframe::App for ScrapingApp {
             match self.current_tab {
                 Tab::Search => {
                     if let Some(results) = self.search_tab.show(ui, &mut self.store_manager) {
+                        // Antes de guardar, para comparar las bajadas con la búsqueda anterior
+                        self.alerts_tab.evaluate(&results, self.price_history.as_ref());
+                        if self.auto_save_results {
+                            self.save_results(&results);
+                        }
                         self.search_results = Some(results);
+                        self.results_tab.reset();
                         self.current_tab = Tab::Results;
                     }
                 },
@@ -82,7 +149,13 @@ impl eframe::App for ScrapingApp {
                     self.store_tab.show(ui, &mut self.store_manager);
                 },
                 Tab::Results => {
-                    self.results_tab.show(ui, self.search_results.as_ref());
+                    self.results_tab.show(ui, self.search_results.as_ref(), &self.store_manager);
+                },
+                Tab::Alerts => {
+                    self.alerts_tab.show(ui);
+                },
+                Tab::Schedules => {
+                    self.schedules_tab.show(ui, &self.store_manager);
                 },
             }
         });
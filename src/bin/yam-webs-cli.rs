@@ -0,0 +1,409 @@
+use std::io::Write;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use yam_webs::models::{Product, SearchHistoryEntry, StoreConfig, StoreManager};
+use yam_webs::scraping::{Scheduler, SearchEngine, SearchEvent, WebScraper};
+use yam_webs::utils::{CsvOptions, FileManager, PriceHistory};
+
+// Códigos de salida
+const EXIT_OK: u8 = 0;
+const EXIT_NO_RESULTS: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_CONFIG: u8 = 3;
+const EXIT_ALL_FAILED: u8 = 4;
+const EXIT_PARTIAL: u8 = 5;
+const EXIT_OUTPUT: u8 = 6;
+
+// Cada cuánto mira el daemon si toca alguna búsqueda y relee la configuración
+const DAEMON_TICK: Duration = Duration::from_secs(1);
+const DAEMON_RELOAD: Duration = Duration::from_secs(60);
+
+const USAGE: &str = "\
+Uso:
+  yam-webs-cli search <consulta> [--stores a,b] [--format json|csv] [--out archivo]
+  yam-webs-cli stores
+  yam-webs-cli daemon
+  yam-webs-cli --help
+
+daemon ejecuta las búsquedas programadas de config.json (schedules) hasta que se
+interrumpa el proceso, guardando cada resultado en snapshots/.
+
+Opciones de search:
+  --stores a,b     Tiendas a consultar (por defecto, las habilitadas en stores.json)
+  --format FMT     json (por defecto) o csv (según csv_export en config.json)
+  --out ARCHIVO    Archivo de salida (por defecto, la salida estándar)
+
+Códigos de salida:
+  0 éxito, 1 sin resultados, 2 uso incorrecto, 3 error de configuración,
+  4 todas las tiendas fallaron, 5 algunas tiendas fallaron, 6 error al escribir la salida";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug)]
+struct SearchArgs {
+    query: String,
+    stores: Option<Vec<String>>,
+    format: OutputFormat,
+    out: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let code = match args.first().map(String::as_str) {
+        Some("search") => match parse_search_args(&args[1..]) {
+            Ok(search_args) => run_search(search_args),
+            Err(e) => usage_error(&e),
+        },
+        Some("stores") => list_stores(),
+        Some("daemon") => run_daemon(),
+        Some("--help") | Some("-h") | Some("help") => {
+            println!("{}", USAGE);
+            EXIT_OK
+        }
+        Some(other) => usage_error(&format!("Comando desconocido: {}", other)),
+        None => usage_error("Falta el comando"),
+    };
+
+    ExitCode::from(code)
+}
+
+fn usage_error(message: &str) -> u8 {
+    eprintln!("{}\n\n{}", message, USAGE);
+    EXIT_USAGE
+}
+
+fn parse_search_args(args: &[String]) -> Result<SearchArgs, String> {
+    let mut query = None;
+    let mut stores = None;
+    let mut format = OutputFormat::Json;
+    let mut out = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("Falta el valor de {}", name))
+        };
+
+        match arg.as_str() {
+            "--stores" => {
+                let list = value("--stores")?;
+                stores = Some(
+                    list.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect(),
+                );
+            }
+            "--format" => {
+                format = match value("--format")?.to_lowercase().as_str() {
+                    "json" => OutputFormat::Json,
+                    "csv" => OutputFormat::Csv,
+                    other => return Err(format!("Formato desconocido: {}", other)),
+                };
+            }
+            "--out" => out = Some(value("--out")?),
+            flag if flag.starts_with("--") => return Err(format!("Opción desconocida: {}", flag)),
+            _ if query.is_none() => query = Some(arg.clone()),
+            _ => return Err(format!("Argumento inesperado: {}", arg)),
+        }
+    }
+
+    let query = query
+        .filter(|q| !q.trim().is_empty())
+        .ok_or("Falta la consulta")?;
+
+    Ok(SearchArgs {
+        query,
+        stores,
+        format,
+        out,
+    })
+}
+
+/// Elige las tiendas pedidas por nombre (sin distinguir mayúsculas) o las habilitadas
+fn select_stores(store_manager: &StoreManager, names: Option<&[String]>) -> Result<Vec<StoreConfig>, String> {
+    let Some(names) = names else {
+        return Ok(store_manager.get_enabled_stores().into_iter().cloned().collect());
+    };
+
+    names
+        .iter()
+        .map(|name| {
+            store_manager
+                .stores
+                .iter()
+                .find(|store| store.name.eq_ignore_ascii_case(name))
+                .cloned()
+                .ok_or_else(|| format!("No existe la tienda \"{}\" en stores.json", name))
+        })
+        .collect()
+}
+
+fn run_search(args: SearchArgs) -> u8 {
+    let file_manager = FileManager::new();
+    let store_manager = file_manager.load_stores();
+    let app_config = file_manager.load_app_config();
+
+    let stores = match select_stores(&store_manager, args.stores.as_deref()) {
+        Ok(stores) if stores.is_empty() => {
+            eprintln!("No hay tiendas para buscar");
+            return EXIT_CONFIG;
+        }
+        Ok(stores) => stores,
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_CONFIG;
+        }
+    };
+
+    let store_names: Vec<String> = stores.iter().map(|store| store.name.clone()).collect();
+    let engine = SearchEngine::new(Arc::new(WebScraper::with_config(&app_config)));
+    let started = Instant::now();
+    let handle = engine.start_search(&args.query, stores, || {});
+    let total = handle.total_stores();
+
+    eprintln!("Buscando \"{}\" en {} tiendas...", args.query, total);
+
+    let mut products: Vec<Product> = Vec::new();
+    let mut failed = 0;
+    while let Some(event) = handle.wait_event() {
+        match event {
+            SearchEvent::StoreStarted { store_name } => eprintln!("[{}] buscando...", store_name),
+            SearchEvent::QueryStarted { .. } | SearchEvent::QueryFinished { .. } => {}
+            SearchEvent::StoreFinished { store_name, result } => match result {
+                Ok(found) => {
+                    eprintln!("[{}] {} productos", store_name, found.len());
+                    products.extend(found);
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("[{}] error: {}", store_name, e);
+                }
+            },
+            SearchEvent::Finished => break,
+        }
+    }
+
+    eprintln!(
+        "{} productos de {} tiendas ({} fallidas) en {:.1} s",
+        products.len(),
+        total - failed,
+        failed,
+        started.elapsed().as_secs_f32()
+    );
+
+    // La búsqueda queda en el mismo historial que las de la interfaz
+    let mut search_history = file_manager.load_search_history();
+    search_history.record(SearchHistoryEntry {
+        query: args.query.clone(),
+        stores: store_names,
+        searched_at: chrono::Utc::now(),
+        result_count: products.len(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        pinned: false,
+    });
+    if let Err(e) = file_manager.save_search_history(&search_history) {
+        eprintln!("{}", e);
+    }
+
+    report_alerts(&file_manager, &args.query, &products, app_config.auto_save_results);
+
+    if let Err(e) = write_output(&file_manager, &app_config.csv_export, &products, args.format, args.out.as_deref()) {
+        eprintln!("{}", e);
+        return EXIT_OUTPUT;
+    }
+
+    if failed == total {
+        EXIT_ALL_FAILED
+    } else if failed > 0 {
+        EXIT_PARTIAL
+    } else if products.is_empty() {
+        EXIT_NO_RESULTS
+    } else {
+        EXIT_OK
+    }
+}
+
+/// Evalúa la watchlist y muestra las alertas por stderr; luego añade el lote al historial
+fn report_alerts(file_manager: &FileManager, query: &str, products: &[Product], record_history: bool) {
+    let mut history = PriceHistory::open(FileManager::HISTORY_DB)
+        .map_err(|e| eprintln!("{}", e))
+        .ok();
+
+    let alerts = file_manager.load_watchlist().evaluate(products, history.as_ref());
+    for alert in &alerts {
+        eprintln!("[alerta] {} ({})", alert.message(), alert.product.url);
+    }
+
+    if record_history
+        && let Some(history) = &mut history
+        && let Err(e) = history.record(query, products)
+    {
+        eprintln!("{}", e);
+    }
+}
+
+fn write_output(
+    file_manager: &FileManager,
+    csv_options: &CsvOptions,
+    products: &[Product],
+    format: OutputFormat,
+    out: Option<&str>,
+) -> Result<(), String> {
+    match (format, out) {
+        (OutputFormat::Json, Some(path)) => file_manager.export_to_json(products, path),
+        (OutputFormat::Csv, Some(path)) => file_manager.export_to_csv(products, path, csv_options),
+        (format, None) => {
+            let content = match format {
+                OutputFormat::Json => {
+                    let mut json = file_manager.products_to_json(products)?.into_bytes();
+                    json.push(b'\n');
+                    json
+                }
+                OutputFormat::Csv => file_manager.products_to_csv(products, csv_options),
+            };
+            std::io::stdout()
+                .lock()
+                .write_all(&content)
+                .map_err(|e| format!("Error al escribir en la salida estándar: {}", e))
+        }
+    }
+}
+
+fn list_stores() -> u8 {
+    let store_manager = FileManager::new().load_stores();
+    for store in &store_manager.stores {
+        let status = if store.enabled { "habilitada" } else { "deshabilitada" };
+        println!("{}\t{}\t{}", store.name, status, store.base_url);
+    }
+    EXIT_OK
+}
+
+/// Ejecuta las búsquedas programadas sin interfaz hasta que se interrumpa el proceso
+fn run_daemon() -> u8 {
+    let file_manager = FileManager::new();
+    let mut app_config = file_manager.load_app_config();
+    let mut store_manager = file_manager.load_stores();
+
+    if app_config.schedules.is_empty() {
+        eprintln!("No hay búsquedas programadas en config.json");
+        return EXIT_CONFIG;
+    }
+
+    let mut scheduler = Scheduler::new(Arc::new(WebScraper::with_config(&app_config)));
+    let now = chrono::Utc::now();
+    for schedule in &app_config.schedules {
+        match scheduler.next_run(schedule, now) {
+            _ if !schedule.enabled => eprintln!("[{}] pausada", schedule.name),
+            Ok(next) => eprintln!(
+                "[{}] {}; próxima ejecución {}",
+                schedule.name,
+                schedule.trigger.describe(),
+                next.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            ),
+            Err(e) => eprintln!("[{}] {}", schedule.name, e),
+        }
+    }
+
+    let mut last_reload = Instant::now();
+    loop {
+        // Recoge los cambios hechos desde la interfaz
+        if last_reload.elapsed() >= DAEMON_RELOAD {
+            app_config = file_manager.load_app_config();
+            store_manager = file_manager.load_stores();
+            last_reload = Instant::now();
+        }
+
+        scheduler.start_due(&app_config.schedules, &store_manager, chrono::Utc::now(), || {});
+
+        for completed in scheduler.poll() {
+            eprintln!(
+                "[{}] {} productos para \"{}\"{}",
+                completed.schedule_name,
+                completed.run.product_count,
+                completed.query,
+                completed.run.snapshot.as_deref().map(|path| format!(" en {}", path)).unwrap_or_default()
+            );
+            for error in &completed.run.errors {
+                eprintln!("[{}] error: {}", completed.schedule_name, error);
+            }
+            report_alerts(&file_manager, &completed.query, &completed.products, app_config.auto_save_results);
+        }
+
+        std::thread::sleep(DAEMON_TICK);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn search_args_with_every_option() {
+        let parsed = parse_search_args(&args(&[
+            "taladro percutor",
+            "--stores",
+            "Amazon, ,Leroy",
+            "--format",
+            "CSV",
+            "--out",
+            "salida.csv",
+        ]))
+        .unwrap();
+
+        assert_eq!(parsed.query, "taladro percutor");
+        assert_eq!(parsed.stores, Some(args(&["Amazon", "Leroy"])));
+        assert_eq!(parsed.format, OutputFormat::Csv);
+        assert_eq!(parsed.out.as_deref(), Some("salida.csv"));
+    }
+
+    #[test]
+    fn search_args_defaults() {
+        let parsed = parse_search_args(&args(&["taladro"])).unwrap();
+        assert_eq!(parsed.stores, None);
+        assert_eq!(parsed.format, OutputFormat::Json);
+        assert_eq!(parsed.out, None);
+    }
+
+    #[test]
+    fn bad_search_args_are_usage_errors() {
+        let error = |list: &[&str]| parse_search_args(&args(list)).unwrap_err();
+
+        assert_eq!(error(&[]), "Falta la consulta");
+        assert_eq!(error(&["  "]), "Falta la consulta");
+        assert_eq!(error(&["taladro", "--out"]), "Falta el valor de --out");
+        assert_eq!(error(&["taladro", "--format", "xml"]), "Formato desconocido: xml");
+        assert_eq!(error(&["taladro", "--verbose"]), "Opción desconocida: --verbose");
+        assert_eq!(error(&["taladro", "sierra"]), "Argumento inesperado: sierra");
+    }
+
+    #[test]
+    fn stores_are_selected_by_name_or_enabled() {
+        let mut store_manager = StoreManager::new();
+        let mut disabled = StoreConfig::new("Leroy".to_string(), "https://leroy.example".to_string());
+        disabled.enabled = false;
+        store_manager.add_store(StoreConfig::new("Amazon".to_string(), "https://amazon.example".to_string()));
+        store_manager.add_store(disabled);
+
+        let names = |stores: Vec<StoreConfig>| stores.into_iter().map(|store| store.name).collect::<Vec<_>>();
+
+        assert_eq!(names(select_stores(&store_manager, None).unwrap()), ["Amazon"]);
+        let wanted = args(&["leroy", "AMAZON"]);
+        assert_eq!(names(select_stores(&store_manager, Some(&wanted)).unwrap()), ["Leroy", "Amazon"]);
+
+        let error = select_stores(&store_manager, Some(&args(&["Ikea"]))).unwrap_err();
+        assert_eq!(error, "No existe la tienda \"Ikea\" en stores.json");
+    }
+}
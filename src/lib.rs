@@ -0,0 +1,5 @@
+pub mod app;
+pub mod models;
+pub mod scraping;
+pub mod ui;
+pub mod utils;
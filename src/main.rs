@@ -1,10 +1,4 @@
-mod app;
-mod models;
-mod scraping;
-mod ui;
-mod utils;
-
-use app::ScrapingApp;
+use yam_webs::app::ScrapingApp;
 
 fn main() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
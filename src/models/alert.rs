@@ -0,0 +1,289 @@
+use crate::models::{Price, Product};
+use crate::utils::PriceHistory;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Qué productos vigila una regla
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AlertTarget {
+    /// Productos cuyo nombre contiene todas las palabras de la consulta; sin tienda, en cualquiera
+    Query { query: String, store: Option<String> },
+    /// Un producto concreto, identificado por su URL
+    Url { url: String },
+}
+
+/// Cuándo salta la alerta
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AlertCondition {
+    /// El precio es menor que el monto
+    PriceBelow { amount: Decimal },
+    /// El precio bajó al menos este porcentaje respecto a la observación anterior del historial
+    PriceDrop { percent: Decimal },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub enabled: bool,
+    pub target: AlertTarget,
+    pub condition: AlertCondition,
+}
+
+/// Lista de reglas de alerta, guardada en watchlist.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub rules: Vec<AlertRule>,
+}
+
+/// Un producto que cumplió una regla
+#[derive(Debug, Clone)]
+pub struct AlertMatch {
+    pub rule_name: String,
+    pub product: Product,
+    // Precio anterior, sólo en las alertas de bajada
+    pub previous_price: Option<Price>,
+    pub matched_at: DateTime<Utc>,
+}
+
+impl AlertRule {
+    /// Descripción corta de la regla para mostrar en la UI y la CLI
+    pub fn describe(&self) -> String {
+        let target = match &self.target {
+            AlertTarget::Query { query, store: Some(store) } => format!("\"{}\" en {}", query, store),
+            AlertTarget::Query { query, store: None } => format!("\"{}\" en cualquier tienda", query),
+            AlertTarget::Url { url } => url.clone(),
+        };
+        let condition = match &self.condition {
+            AlertCondition::PriceBelow { amount } => format!("precio < {}", amount),
+            AlertCondition::PriceDrop { percent } => format!("baja de {}%", percent),
+        };
+        format!("{}, {}", target, condition)
+    }
+
+    /// Indica si la regla vigila este producto
+    pub fn applies_to(&self, product: &Product) -> bool {
+        match &self.target {
+            AlertTarget::Query { query, store } => {
+                let in_store = store
+                    .as_ref()
+                    .is_none_or(|store| store.trim().eq_ignore_ascii_case(product.store_name.trim()));
+                let name = product.name.to_lowercase();
+                let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+                in_store && !words.is_empty() && words.iter().all(|word| name.contains(word.as_str()))
+            }
+            AlertTarget::Url { url } => normalize_url(url) == normalize_url(&product.url),
+        }
+    }
+
+    /// Comprueba la condición; la bajada se mide contra la última observación del historial
+    pub fn check(&self, product: &Product, history: Option<&PriceHistory>) -> Option<AlertMatch> {
+        if !self.enabled || !self.applies_to(product) {
+            return None;
+        }
+        let amount = product.price.amount?;
+
+        let previous_price = match &self.condition {
+            AlertCondition::PriceBelow { amount: limit } => {
+                if amount >= *limit {
+                    return None;
+                }
+                None
+            }
+            AlertCondition::PriceDrop { percent } => {
+                let previous = history?.latest_observation(product).ok().flatten()?.price;
+                let previous_amount = previous.amount.filter(|a| !a.is_zero())?;
+                if same_currency(&previous, &product.price) == Some(false) {
+                    return None;
+                }
+
+                let drop = (previous_amount - amount) / previous_amount * Decimal::ONE_HUNDRED;
+                if drop <= Decimal::ZERO || drop < *percent {
+                    return None;
+                }
+                Some(previous)
+            }
+        };
+
+        Some(AlertMatch {
+            rule_name: self.name.clone(),
+            product: product.clone(),
+            previous_price,
+            matched_at: Utc::now(),
+        })
+    }
+}
+
+impl Watchlist {
+    /// Evalúa todas las reglas activas contra un lote de resultados.
+    /// Debe llamarse antes de añadir el lote al historial para que las bajadas se comparen
+    /// con la búsqueda anterior.
+    pub fn evaluate(&self, products: &[Product], history: Option<&PriceHistory>) -> Vec<AlertMatch> {
+        self.rules
+            .iter()
+            .flat_map(|rule| products.iter().filter_map(move |product| rule.check(product, history)))
+            .collect()
+    }
+}
+
+impl AlertMatch {
+    /// Texto de la alerta para la UI y la CLI
+    pub fn message(&self) -> String {
+        let price = self.product.price.formatted();
+        match &self.previous_price {
+            Some(previous) => format!(
+                "{}: {} en {} bajó de {} a {}",
+                self.rule_name,
+                self.product.name,
+                self.product.store_name,
+                previous.formatted(),
+                price
+            ),
+            None => format!(
+                "{}: {} en {} a {}",
+                self.rule_name, self.product.name, self.product.store_name, price
+            ),
+        }
+    }
+}
+
+/// None si alguno de los dos precios no tiene moneda
+fn same_currency(a: &Price, b: &Price) -> Option<bool> {
+    Some(a.currency.as_ref()?.eq_ignore_ascii_case(b.currency.as_ref()?))
+}
+
+fn normalize_url(url: &str) -> &str {
+    url.split('#').next().unwrap_or_default().trim().trim_end_matches('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PriceLocale;
+    use chrono::Duration;
+
+    fn product(name: &str, store: &str, url: &str, price: &str) -> Product {
+        Product::new(
+            name.to_string(),
+            Price::parse(price, &PriceLocale::default()),
+            url.to_string(),
+            String::new(),
+            store.to_string(),
+        )
+    }
+
+    fn rule(target: AlertTarget, condition: AlertCondition) -> AlertRule {
+        AlertRule {
+            name: "Regla".to_string(),
+            enabled: true,
+            target,
+            condition,
+        }
+    }
+
+    fn query(query: &str, store: Option<&str>) -> AlertTarget {
+        AlertTarget::Query {
+            query: query.to_string(),
+            store: store.map(str::to_string),
+        }
+    }
+
+    fn below(amount: i64) -> AlertCondition {
+        AlertCondition::PriceBelow {
+            amount: Decimal::from(amount),
+        }
+    }
+
+    fn drop(percent: i64) -> AlertCondition {
+        AlertCondition::PriceDrop {
+            percent: Decimal::from(percent),
+        }
+    }
+
+    fn history_with(products: &[Product]) -> PriceHistory {
+        let mut history = PriceHistory::open_in_memory().unwrap();
+        history.record_at("q", products, Utc::now() - Duration::days(1)).unwrap();
+        history
+    }
+
+    const URL: &str = "https://tienda.example/p/1";
+
+    #[test]
+    fn price_below_is_strict() {
+        let cheap = rule(query("taladro", None), below(100));
+        assert!(cheap.check(&product("Taladro 600W", "Tienda", URL, "99,99"), None).is_some());
+        assert!(cheap.check(&product("Taladro 600W", "Tienda", URL, "100"), None).is_none());
+        assert!(cheap.check(&product("Taladro 600W", "Tienda", URL, "Consultar"), None).is_none());
+
+        let disabled = AlertRule {
+            enabled: false,
+            ..cheap
+        };
+        assert!(disabled.check(&product("Taladro 600W", "Tienda", URL, "50"), None).is_none());
+    }
+
+    #[test]
+    fn query_target_needs_every_word_and_the_store() {
+        let anywhere = rule(query("Taladro percutor", None), below(1000));
+        assert!(anywhere.applies_to(&product("TALADRO Percutor 600W", "Otra", URL, "10")));
+        assert!(!anywhere.applies_to(&product("Taladro 600W", "Tienda", URL, "10")));
+
+        let in_store = rule(query("taladro", Some(" tienda ")), below(1000));
+        assert!(in_store.applies_to(&product("Taladro", "Tienda", URL, "10")));
+        assert!(!in_store.applies_to(&product("Taladro", "Otra", URL, "10")));
+
+        assert!(!rule(query("  ", None), below(1000)).applies_to(&product("Taladro", "Tienda", URL, "10")));
+    }
+
+    #[test]
+    fn url_target_ignores_fragment_and_trailing_slash() {
+        let exact = rule(AlertTarget::Url { url: format!("{}/", URL) }, below(1000));
+        assert!(exact.applies_to(&product("Taladro", "Tienda", &format!("{}#opiniones", URL), "10")));
+        assert!(!exact.applies_to(&product("Taladro", "Tienda", "https://tienda.example/p/2", "10")));
+    }
+
+    #[test]
+    fn price_drop_compares_with_the_latest_observation() {
+        let history = history_with(&[product("Taladro", "Tienda", URL, "100")]);
+        let ten_percent = rule(AlertTarget::Url { url: URL.to_string() }, drop(10));
+
+        let matched = ten_percent.check(&product("Taladro", "Tienda", URL, "90"), Some(&history)).unwrap();
+        assert_eq!(matched.previous_price.unwrap().amount, Some(Decimal::from(100)));
+        assert!(ten_percent.check(&product("Taladro", "Tienda", URL, "91"), Some(&history)).is_none());
+        assert!(ten_percent.check(&product("Taladro", "Tienda", URL, "120"), Some(&history)).is_none());
+
+        // Sin historial no hay con qué comparar
+        assert!(ten_percent.check(&product("Taladro", "Tienda", URL, "10"), None).is_none());
+        let empty = PriceHistory::open_in_memory().unwrap();
+        assert!(ten_percent.check(&product("Taladro", "Tienda", URL, "10"), Some(&empty)).is_none());
+    }
+
+    #[test]
+    fn price_drop_needs_the_same_currency() {
+        let history = history_with(&[product("Taladro", "Tienda", URL, "US$ 100")]);
+        let any_drop = rule(AlertTarget::Url { url: URL.to_string() }, drop(1));
+        assert!(any_drop.check(&product("Taladro", "Tienda", URL, "€ 50"), Some(&history)).is_none());
+        assert!(any_drop.check(&product("Taladro", "Tienda", URL, "US$ 50"), Some(&history)).is_some());
+        // Si a uno le falta la moneda, se compara igual
+        assert!(any_drop.check(&product("Taladro", "Tienda", URL, "50"), Some(&history)).is_some());
+    }
+
+    #[test]
+    fn watchlist_evaluates_every_rule_against_every_product() {
+        let watchlist = Watchlist {
+            rules: vec![rule(query("taladro", None), below(100)), rule(query("lijadora", None), below(100))],
+        };
+        let products = [
+            product("Taladro", "Tienda", URL, "50"),
+            product("Taladro", "Otra", URL, "150"),
+            product("Lijadora", "Tienda", URL, "80"),
+        ];
+        let matches = watchlist.evaluate(&products, None);
+        let names: Vec<(&str, &str)> = matches
+            .iter()
+            .map(|m| (m.product.name.as_str(), m.product.store_name.as_str()))
+            .collect();
+        assert_eq!(names, [("Taladro", "Tienda"), ("Lijadora", "Tienda")]);
+    }
+}
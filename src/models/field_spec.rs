@@ -0,0 +1,336 @@
+use crate::models::{ExtraValue, Price, PriceLocale};
+use regex::Regex;
+use scraper::Selector;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::sync::Mutex;
+
+/// Parte del elemento encontrado de la que se toma el valor
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldSource {
+    /// Todo el texto del elemento y sus descendientes
+    #[default]
+    Text,
+    /// Sólo el texto directo del elemento, sin el de sus hijos
+    OwnText,
+    /// Valor de un atributo, como "href", "data-price" o "aria-label"
+    Attribute(String),
+    /// HTML interno del elemento
+    InnerHtml,
+}
+
+/// Transformación aplicada al valor extraído, en orden
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Transform {
+    Trim,
+    /// Reduce cualquier secuencia de espacios y saltos de línea a un solo espacio
+    CollapseWhitespace,
+    Replace { from: String, to: String },
+    Prefix(String),
+    Suffix(String),
+}
+
+impl Transform {
+    pub fn apply(&self, value: String) -> String {
+        match self {
+            Transform::Trim => value.trim().to_string(),
+            Transform::CollapseWhitespace => value.split_whitespace().collect::<Vec<_>>().join(" "),
+            Transform::Replace { from, to } if !from.is_empty() => value.replace(from.as_str(), to),
+            Transform::Replace { .. } => value,
+            Transform::Prefix(prefix) => format!("{}{}", prefix, value),
+            Transform::Suffix(suffix) => format!("{}{}", value, suffix),
+        }
+    }
+}
+
+/// Cómo extraer un campo del producto: selector, origen del valor, captura con
+/// expresión regular opcional y transformaciones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSpec {
+    pub selector: String,
+    #[serde(default)]
+    pub source: FieldSource,
+    #[serde(default)]
+    pub regex: Option<String>,
+    // Grupo de captura de la expresión; 0 es la coincidencia completa
+    #[serde(default = "default_capture_group")]
+    pub capture_group: usize,
+    #[serde(default)]
+    pub transforms: Vec<Transform>,
+    #[serde(skip)]
+    compiled: CompiledRegex,
+}
+
+/// Expresión regular ya compilada junto al patrón del que salió; si el patrón cambia
+/// (por ejemplo al editarlo en la interfaz) se vuelve a compilar
+#[derive(Default)]
+struct CompiledRegex(Mutex<Option<(String, Result<Regex, String>)>>);
+
+impl CompiledRegex {
+    fn get(&self, pattern: &str) -> Result<Regex, String> {
+        let mut compiled = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match &*compiled {
+            Some((cached, result)) if cached == pattern => result.clone(),
+            _ => {
+                let result = Regex::new(pattern).map_err(|e| format!("Expresión regular inválida: {}", e));
+                *compiled = Some((pattern.to_string(), result.clone()));
+                result
+            }
+        }
+    }
+}
+
+impl Clone for CompiledRegex {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()))
+    }
+}
+
+// Es sólo una caché: dos especificaciones iguales lo son aunque una ya haya compilado
+impl PartialEq for CompiledRegex {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for CompiledRegex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CompiledRegex")
+    }
+}
+
+fn default_capture_group() -> usize {
+    1
+}
+
+impl Default for FieldSpec {
+    fn default() -> Self {
+        Self::text("")
+    }
+}
+
+impl FieldSpec {
+    pub fn new(selector: impl Into<String>, source: FieldSource) -> Self {
+        Self {
+            selector: selector.into(),
+            source,
+            regex: None,
+            capture_group: default_capture_group(),
+            transforms: vec![Transform::Trim],
+            compiled: CompiledRegex::default(),
+        }
+    }
+
+    /// Texto del elemento, recortado (lo que hacía un selector simple)
+    pub fn text(selector: impl Into<String>) -> Self {
+        Self::new(selector, FieldSource::Text)
+    }
+
+    pub fn attribute(selector: impl Into<String>, attribute: &str) -> Self {
+        Self::new(selector, FieldSource::Attribute(attribute.to_string()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selector.trim().is_empty()
+    }
+
+    /// Comprueba que el selector CSS y la expresión regular sean válidos
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.is_empty() {
+            Selector::parse(&self.selector).map_err(|e| format!("Selector CSS inválido: {}", e))?;
+        }
+        self.compiled_regex().map(|_| ())
+    }
+
+    /// Aplica la captura y las transformaciones a un valor crudo; vacío cuenta como ausente.
+    /// Falla si la expresión regular no compila, con el mismo mensaje que `validate`.
+    pub fn process(&self, raw: String) -> Result<Option<String>, String> {
+        let mut value = match self.compiled_regex()? {
+            Some(regex) => {
+                let captured = regex
+                    .captures(&raw)
+                    .and_then(|captures| captures.get(self.capture_group).map(|m| m.as_str().to_string()));
+                match captured {
+                    Some(captured) => captured,
+                    None => return Ok(None),
+                }
+            }
+            None => raw,
+        };
+
+        for transform in &self.transforms {
+            value = transform.apply(value);
+        }
+
+        Ok((!value.trim().is_empty()).then_some(value))
+    }
+
+    /// La expresión regular, compilada sólo la primera vez que se usa
+    fn compiled_regex(&self) -> Result<Option<Regex>, String> {
+        match self.regex.as_deref().filter(|pattern| !pattern.is_empty()) {
+            Some(pattern) => self.compiled.get(pattern).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Tipo al que se convierte un campo personalizado
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustomFieldKind {
+    #[default]
+    Text,
+    /// Número con el formato de precios de la tienda, como "4,5 de 5"
+    Number,
+    /// Sí si el selector encuentra un valor, no en caso contrario
+    Flag,
+}
+
+/// Campo adicional que declara una tienda y se guarda en `Product::extra`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomField {
+    pub name: String,
+    #[serde(default)]
+    pub kind: CustomFieldKind,
+    #[serde(deserialize_with = "deserialize_text")]
+    pub spec: FieldSpec,
+}
+
+impl CustomField {
+    pub fn new(name: impl Into<String>, kind: CustomFieldKind, spec: FieldSpec) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            spec,
+        }
+    }
+
+    /// Convierte el valor extraído al tipo del campo
+    pub fn to_value(&self, extracted: Option<String>, locale: &PriceLocale) -> Option<ExtraValue> {
+        match self.kind {
+            CustomFieldKind::Text => extracted.map(ExtraValue::Text),
+            CustomFieldKind::Number => extracted
+                .and_then(|text| Price::parse(&text, locale).amount)
+                .map(ExtraValue::Number),
+            CustomFieldKind::Flag => Some(ExtraValue::Flag(extracted.is_some())),
+        }
+    }
+}
+
+/// Forma en disco: los stores.json antiguos guardan sólo el selector como texto
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FieldSpecRepr {
+    Selector(String),
+    Spec(FieldSpec),
+}
+
+impl FieldSpecRepr {
+    fn into_spec(self, legacy_source: FieldSource) -> FieldSpec {
+        match self {
+            FieldSpecRepr::Selector(selector) => FieldSpec::new(selector, legacy_source),
+            FieldSpecRepr::Spec(spec) => spec,
+        }
+    }
+}
+
+pub(crate) fn deserialize_text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FieldSpec, D::Error> {
+    Ok(FieldSpecRepr::deserialize(deserializer)?.into_spec(FieldSource::Text))
+}
+
+pub(crate) fn deserialize_optional_text<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<FieldSpec>, D::Error> {
+    Ok(Option::<FieldSpecRepr>::deserialize(deserializer)?.map(|repr| repr.into_spec(FieldSource::Text)))
+}
+
+/// Los enlaces antiguos se leían del atributo href
+pub(crate) fn deserialize_href<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FieldSpec, D::Error> {
+    Ok(FieldSpecRepr::deserialize(deserializer)?.into_spec(FieldSource::Attribute("href".to_string())))
+}
+
+/// Las imágenes antiguas se leían del atributo src
+pub(crate) fn deserialize_src<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FieldSpec, D::Error> {
+    Ok(FieldSpecRepr::deserialize(deserializer)?.into_spec(FieldSource::Attribute("src".to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn spec(regex: &str) -> FieldSpec {
+        FieldSpec {
+            regex: Some(regex.to_string()),
+            ..FieldSpec::text(".rating")
+        }
+    }
+
+    #[test]
+    fn captures_group_and_applies_transforms() {
+        let mut rating = spec(r"(\d+[.,]\d+) de 5");
+        rating.transforms.push(Transform::Suffix("/5".to_string()));
+        assert_eq!(rating.process("Valoración: 4,5 de 5".to_string()), Ok(Some("4,5/5".to_string())));
+        assert_eq!(rating.process("Sin valoraciones".to_string()), Ok(None));
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error_not_an_empty_value() {
+        let broken = spec("(sin cerrar");
+        let error = broken.process("texto".to_string()).unwrap_err();
+        assert_eq!(Err(error), broken.validate());
+    }
+
+    #[test]
+    fn invalid_selector_fails_validation() {
+        assert!(FieldSpec::text(".precio[").validate().is_err());
+        assert!(FieldSpec::text(".precio").validate().is_ok());
+        assert!(FieldSpec::default().validate().is_ok());
+    }
+
+    #[test]
+    fn edited_pattern_is_recompiled() {
+        let mut rating = spec(r"(\d+)");
+        assert_eq!(rating.process("4 de 5".to_string()), Ok(Some("4".to_string())));
+        rating.regex = Some(r"de (\d+)".to_string());
+        assert_eq!(rating.process("4 de 5".to_string()), Ok(Some("5".to_string())));
+        assert_eq!(rating.clone(), rating);
+    }
+
+    #[test]
+    fn number_field_follows_the_store_locale() {
+        let rating = CustomField::new("Valoración", CustomFieldKind::Number, FieldSpec::text(".rating"));
+        let locale = PriceLocale {
+            decimal_separator: Some(','),
+            ..PriceLocale::default()
+        };
+
+        assert_eq!(
+            rating.to_value(Some("4,5".to_string()), &locale),
+            Some(ExtraValue::Number(Decimal::new(45, 1)))
+        );
+        assert_eq!(rating.to_value(Some("sin valorar".to_string()), &locale), None);
+        assert_eq!(rating.to_value(None, &locale), None);
+    }
+
+    #[test]
+    fn flag_field_is_set_only_when_something_matches() {
+        let out_of_stock = CustomField::new("Agotado", CustomFieldKind::Flag, FieldSpec::text(".agotado"));
+        let locale = PriceLocale::default();
+
+        assert_eq!(out_of_stock.to_value(Some("Agotado".to_string()), &locale), Some(ExtraValue::Flag(true)));
+        assert_eq!(out_of_stock.to_value(Some(String::new()), &locale), Some(ExtraValue::Flag(true)));
+        assert_eq!(out_of_stock.to_value(None, &locale), Some(ExtraValue::Flag(false)));
+    }
+
+    #[test]
+    fn text_field_keeps_the_value() {
+        let brand = CustomField::new("Marca", CustomFieldKind::Text, FieldSpec::text(".marca"));
+        let locale = PriceLocale::default();
+
+        assert_eq!(
+            brand.to_value(Some("Bosch".to_string()), &locale),
+            Some(ExtraValue::Text("Bosch".to_string()))
+        );
+        assert_eq!(brand.to_value(None, &locale), None);
+    }
+}
@@ -0,0 +1,361 @@
+use crate::models::Product;
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+// Proporción mínima de palabras compartidas para considerar dos nombres el mismo producto
+const MIN_WORD_OVERLAP: f64 = 0.75;
+
+// Unidades que se pegan al número anterior: "128 GB" -> "128gb"
+const UNITS: &[&str] = &[
+    "gb", "tb", "mb", "kg", "g", "mg", "l", "ml", "cm", "mm", "m", "w", "kw", "v", "mah", "hz", "mhz", "ghz",
+    "pulgadas", "pulg", "in", "oz", "lb",
+];
+
+// Palabras que no ayudan a distinguir productos: conectores, categorías genéricas y colores
+const STOPWORDS: &[&str] = &[
+    "de", "del", "la", "el", "los", "las", "y", "con", "para", "en", "sin", "por", "un", "una", "the", "and", "with",
+    "for", "of", "nuevo", "nueva", "new", "original", "oferta", "celular", "smartphone", "telefono", "movil",
+    "libre", "desbloqueado", "unlocked", "negro", "blanco", "azul", "rojo", "verde", "gris", "plata", "dorado",
+    "rosa", "morado", "black", "white", "blue", "red", "green", "gray", "grey", "silver", "gold", "pink", "purple",
+];
+
+// Palabras de variantes y accesorios: si sólo uno de los dos nombres la tiene, no son el mismo producto
+const VARIANT_WORDS: &[&str] = &[
+    "pro", "max", "plus", "mini", "ultra", "lite", "air", "se", "fe", "ti", "super", "funda", "case", "carcasa",
+    "cargador", "charger", "cable", "protector", "mica", "vidrio", "soporte", "adaptador", "repuesto",
+];
+
+/// Productos que parecen el mismo artículo en distintas tiendas
+#[derive(Debug, Clone)]
+pub struct ProductGroup {
+    // Índices en la lista de productos agrupada
+    pub members: Vec<usize>,
+    // El miembro más barato con precio interpretable
+    pub cheapest: Option<usize>,
+}
+
+impl ProductGroup {
+    /// Precio mínimo y máximo del grupo, sólo entre precios de la misma moneda que el más barato
+    pub fn price_spread(&self, products: &[Product]) -> Option<(Decimal, Decimal)> {
+        let cheapest = &products[self.cheapest?].price;
+        let amounts: Vec<Decimal> = self
+            .members
+            .iter()
+            .map(|&i| &products[i].price)
+            .filter(|price| price.currency == cheapest.currency)
+            .filter_map(|price| price.amount)
+            .collect();
+
+        Some((*amounts.iter().min()?, *amounts.iter().max()?))
+    }
+
+    /// Número de tiendas distintas del grupo
+    pub fn store_count(&self, products: &[Product]) -> usize {
+        self.members
+            .iter()
+            .map(|&i| products[i].store_name.as_str())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+/// Rasgos normalizados de un producto para compararlo con otros
+#[derive(Debug, Clone)]
+pub struct ProductSignature {
+    words: HashSet<String>,
+    numbers: HashSet<String>,
+    // Cantidades con unidad, como "128gb"
+    quantities: HashSet<String>,
+    gtin: Option<String>,
+    sku: Option<String>,
+    brand: Option<String>,
+    // Los SKU internos sólo son comparables dentro de la misma tienda
+    store: String,
+}
+
+impl ProductSignature {
+    pub fn new(product: &Product) -> Self {
+        let mut signature = Self {
+            words: HashSet::new(),
+            numbers: HashSet::new(),
+            quantities: HashSet::new(),
+            gtin: product.gtin.as_deref().and_then(normalize_gtin),
+            sku: product
+                .sku
+                .as_deref()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty()),
+            brand: product
+                .brand
+                .as_deref()
+                .map(normalize_text)
+                .filter(|s| !s.is_empty()),
+            store: product.store_name.trim().to_lowercase(),
+        };
+
+        let tokens: Vec<String> = normalize_text(&product.name)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            let is_number = token.chars().all(|c| c.is_ascii_digit());
+
+            if is_number && tokens.get(i + 1).is_some_and(|next| UNITS.contains(&next.as_str())) {
+                signature.quantities.insert(format!("{}{}", token, tokens[i + 1]));
+                i += 2;
+                continue;
+            }
+
+            if is_number {
+                signature.numbers.insert(token.clone());
+            } else if split_quantity(token).is_some() {
+                signature.quantities.insert(token.clone());
+            } else if token.chars().any(|c| c.is_ascii_digit()) {
+                // Modelos como "a2846" o "rtx4070" identifican mucho
+                signature.numbers.insert(token.clone());
+            } else if !STOPWORDS.contains(&token.as_str()) {
+                signature.words.insert(token.clone());
+            }
+            i += 1;
+        }
+
+        signature
+    }
+
+    /// Decide si dos firmas corresponden al mismo producto
+    pub fn matches(&self, other: &ProductSignature) -> bool {
+        // Un código de barras manda sobre el nombre
+        if let (Some(a), Some(b)) = (&self.gtin, &other.gtin) {
+            return a == b;
+        }
+        // Cada tienda numera sus productos a su manera: un mismo SKU en dos tiendas sólo
+        // cuenta si parece un número de parte del fabricante
+        if let (Some(a), Some(b)) = (&self.sku, &other.sku)
+            && a == b
+            && (self.store == other.store || is_part_number(a))
+        {
+            return true;
+        }
+        if let (Some(a), Some(b)) = (&self.brand, &other.brand)
+            && a != b
+        {
+            return false;
+        }
+
+        let variant = |words: &HashSet<String>| {
+            words
+                .iter()
+                .filter(|w| VARIANT_WORDS.contains(&w.as_str()))
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
+        if variant(&self.words) != variant(&other.words) {
+            return false;
+        }
+
+        if self.quantities_conflict(other) || !self.numbers_compatible(other) {
+            return false;
+        }
+
+        self.word_overlap(other) >= MIN_WORD_OVERLAP
+    }
+
+    /// Misma unidad con distinto valor: "128gb" frente a "256gb"
+    fn quantities_conflict(&self, other: &ProductSignature) -> bool {
+        self.quantities.iter().any(|a| {
+            let Some((value_a, unit_a)) = split_quantity(a) else {
+                return false;
+            };
+            other.quantities.iter().any(|b| {
+                split_quantity(b).is_some_and(|(value_b, unit_b)| unit_a == unit_b && value_a != value_b)
+            })
+        })
+    }
+
+    /// Los números del nombre más corto tienen que aparecer en el otro
+    fn numbers_compatible(&self, other: &ProductSignature) -> bool {
+        let (small, large) = if self.numbers.len() <= other.numbers.len() {
+            (&self.numbers, &other.numbers)
+        } else {
+            (&other.numbers, &self.numbers)
+        };
+        small.is_subset(large)
+    }
+
+    /// Palabras compartidas sobre las del nombre más corto
+    fn word_overlap(&self, other: &ProductSignature) -> f64 {
+        let shortest = self.words.len().min(other.words.len());
+        if shortest == 0 {
+            // Sin palabras sólo quedan los números, que ya se compararon
+            return if self.numbers.is_empty() && self.quantities.is_empty() { 0.0 } else { 1.0 };
+        }
+        self.words.intersection(&other.words).count() as f64 / shortest as f64
+    }
+}
+
+/// Agrupa los productos que parecen el mismo artículo. Cada producto se compara con el
+/// primero de cada grupo (o con cualquiera si comparten GTIN) para no encadenar parecidos.
+pub fn group_products(products: &[Product], indices: &[usize]) -> Vec<ProductGroup> {
+    let signatures: Vec<ProductSignature> = indices.iter().map(|&i| ProductSignature::new(&products[i])).collect();
+    // Posiciones dentro de `indices`
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+
+    for (position, signature) in signatures.iter().enumerate() {
+        let found = groups.iter_mut().find(|group| {
+            signatures[group[0]].matches(signature)
+                || (signature.gtin.is_some()
+                    && group.iter().any(|&member| signatures[member].gtin == signature.gtin))
+        });
+
+        match found {
+            Some(group) => group.push(position),
+            None => groups.push(vec![position]),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let members: Vec<usize> = group.into_iter().map(|position| indices[position]).collect();
+            let cheapest = members
+                .iter()
+                .copied()
+                .filter(|&i| products[i].price.amount.is_some())
+                .min_by(|&a, &b| products[a].price.cmp_amount(&products[b].price));
+            ProductGroup { members, cheapest }
+        })
+        .collect()
+}
+
+/// Minúsculas, sin tildes y sin signos de puntuación
+fn normalize_text(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'ä' | 'â' => 'a',
+            'é' | 'è' | 'ë' | 'ê' => 'e',
+            'í' | 'ì' | 'ï' | 'î' => 'i',
+            'ó' | 'ò' | 'ö' | 'ô' => 'o',
+            'ú' | 'ù' | 'ü' | 'û' => 'u',
+            'ñ' => 'n',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect()
+}
+
+/// Separa "128gb" en ("128", "gb") si la unidad es conocida
+fn split_quantity(token: &str) -> Option<(&str, &str)> {
+    let split = token.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = token.split_at(split);
+    (!value.is_empty() && UNITS.contains(&unit)).then_some((value, unit))
+}
+
+/// Número de parte del fabricante ("MQ8N3LL/A", "SM-S911B"): mezcla letras y dígitos y
+/// es lo bastante largo como para no coincidir por casualidad
+fn is_part_number(sku: &str) -> bool {
+    let alphanumeric = sku.chars().filter(|c| c.is_alphanumeric()).count();
+    alphanumeric >= 6 && sku.chars().any(|c| c.is_ascii_digit()) && sku.chars().any(|c| c.is_alphabetic())
+}
+
+/// Sólo dígitos y sin ceros a la izquierda, para que GTIN-12 y GTIN-13 coincidan
+fn normalize_gtin(gtin: &str) -> Option<String> {
+    let digits: String = gtin.chars().filter(|c| c.is_ascii_digit()).collect();
+    let trimmed = digits.trim_start_matches('0');
+    (digits.len() >= 8).then(|| trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Price, PriceLocale};
+
+    fn product(store: &str, name: &str) -> Product {
+        Product::new(
+            name.to_string(),
+            Price::parse("100", &PriceLocale::default()),
+            String::new(),
+            String::new(),
+            store.to_string(),
+        )
+    }
+
+    fn same(a: &Product, b: &Product) -> bool {
+        ProductSignature::new(a).matches(&ProductSignature::new(b))
+    }
+
+    #[test]
+    fn similar_names_match() {
+        assert!(same(
+            &product("A", "Apple iPhone 15 128 GB Negro"),
+            &product("B", "iPhone 15 128GB apple")
+        ));
+    }
+
+    #[test]
+    fn different_capacity_or_variant_do_not_match() {
+        assert!(!same(&product("A", "iPhone 15 128GB"), &product("B", "iPhone 15 256GB")));
+        assert!(!same(&product("A", "iPhone 15 Pro 128GB"), &product("B", "iPhone 15 128GB")));
+        assert!(!same(&product("A", "Funda iPhone 15"), &product("B", "iPhone 15")));
+    }
+
+    #[test]
+    fn gtin_decides_over_name() {
+        let mut a = product("A", "Taladro percutor");
+        let mut b = product("B", "Taladro percutor");
+        a.gtin = Some("0012345678905".to_string());
+        b.gtin = Some("012345678906".to_string());
+        assert!(!same(&a, &b));
+        b.gtin = Some("12345678905".to_string());
+        assert!(same(&a, &b));
+    }
+
+    #[test]
+    fn store_sku_only_decides_within_the_store() {
+        let mut a = product("A", "Taladro percutor");
+        let mut b = product("B", "Lijadora orbital");
+        a.sku = Some("104522".to_string());
+        b.sku = Some("104522".to_string());
+        assert!(!same(&a, &b));
+
+        let mut c = product("A", "Taladro 600W");
+        c.sku = Some("104522".to_string());
+        assert!(same(&a, &c));
+    }
+
+    #[test]
+    fn part_number_sku_matches_across_stores() {
+        let mut a = product("A", "Smartphone Galaxy");
+        let mut b = product("B", "Samsung S23 Phantom");
+        a.sku = Some("SM-S911B".to_string());
+        b.sku = Some("sm-s911b".to_string());
+        assert!(same(&a, &b));
+    }
+
+    #[test]
+    fn different_brands_do_not_match() {
+        let mut a = product("A", "Taladro 600W");
+        let mut b = product("B", "Taladro 600W");
+        a.brand = Some("Bosch".to_string());
+        b.brand = Some("Makita".to_string());
+        assert!(!same(&a, &b));
+    }
+
+    #[test]
+    fn groups_pick_cheapest_member() {
+        let mut products = vec![
+            product("A", "iPhone 15 128GB"),
+            product("B", "Apple iPhone 15 128 GB"),
+            product("C", "Galaxy S23"),
+        ];
+        products[1].price = Price::parse("90", &PriceLocale::default());
+        let groups = group_products(&products, &[0, 1, 2]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].members, [0, 1]);
+        assert_eq!(groups[0].cheapest, Some(1));
+        assert_eq!(groups[0].store_count(&products), 2);
+    }
+}
@@ -1,5 +1,19 @@
+pub mod alert;
+pub mod bundle;
+pub mod field_spec;
+pub mod matching;
+pub mod price;
 pub mod product;
+pub mod schedule;
+pub mod search_history;
 pub mod store;
 
-pub use product::Product;
-pub use store::{StoreConfig, StoreManager};
\ No newline at end of file
+pub use alert::{AlertCondition, AlertMatch, AlertRule, AlertTarget, Watchlist};
+pub use bundle::{ConflictResolution, ImportConfirmation, ImportSummary, StoreBundle, BUNDLE_FORMAT_VERSION};
+pub use field_spec::{CustomField, CustomFieldKind, FieldSource, FieldSpec, Transform};
+pub use matching::{group_products, ProductGroup, ProductSignature};
+pub use price::{Price, PriceLocale};
+pub use product::{ExtraValue, Product};
+pub use schedule::{ScheduleRun, ScheduleTrigger, ScheduledSearch};
+pub use search_history::{SearchHistory, SearchHistoryEntry};
+pub use store::{ExtractionMode, HttpOverrides, PaginationConfig, PaginationMode, StoreConfig, StoreManager};
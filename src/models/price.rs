@@ -0,0 +1,303 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Pistas de formato de precio propias de cada tienda
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriceLocale {
+    // Si no se indican, se deducen del texto del precio
+    pub decimal_separator: Option<char>,
+    pub thousands_separator: Option<char>,
+    // Moneda a usar cuando el precio sólo trae "$" o ningún símbolo
+    pub default_currency: Option<String>,
+}
+
+/// Precio estructurado: el texto original, el monto y la moneda ISO 4217
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "PriceRepr")]
+pub struct Price {
+    pub raw: String,
+    pub amount: Option<Decimal>,
+    pub currency: Option<String>,
+}
+
+// Los resultados guardados antes de existir `Price` tenían el precio como texto plano
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PriceRepr {
+    Raw(String),
+    Parsed {
+        raw: String,
+        amount: Option<Decimal>,
+        currency: Option<String>,
+    },
+}
+
+impl From<PriceRepr> for Price {
+    fn from(repr: PriceRepr) -> Self {
+        match repr {
+            PriceRepr::Raw(raw) => Price::parse(&raw, &PriceLocale::default()),
+            PriceRepr::Parsed { raw, amount, currency } => Price { raw, amount, currency },
+        }
+    }
+}
+
+// Prefijos que indican "precio desde"
+const FROM_PREFIXES: &[&str] = &["a partir de", "starting at", "desde", "from", "ab"];
+
+// Símbolos de moneda; los más largos primero para que "US$" gane sobre "$"
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("US$", "USD"),
+    ("USD$", "USD"),
+    ("MX$", "MXN"),
+    ("R$", "BRL"),
+    ("C$", "CAD"),
+    ("A$", "AUD"),
+    ("S/.", "PEN"),
+    ("S/", "PEN"),
+    ("Bs.", "BOB"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₩", "KRW"),
+    ("₹", "INR"),
+];
+
+const CURRENCY_CODES: &[&str] = &[
+    "USD", "EUR", "GBP", "MXN", "ARS", "CLP", "COP", "PEN", "BRL", "UYU", "PYG", "BOB", "VES",
+    "CAD", "AUD", "JPY", "CNY", "KRW", "INR", "CHF",
+];
+
+impl Price {
+    /// Interpreta un texto de precio usando las pistas de la tienda
+    pub fn parse(raw: &str, locale: &PriceLocale) -> Self {
+        let text = strip_from_prefix(raw.trim());
+        let currency = detect_currency(text).or_else(|| locale.default_currency.clone());
+        let amount = extract_number(text).and_then(|number| normalize_number(&number, locale));
+
+        Self {
+            raw: raw.trim().to_string(),
+            amount,
+            currency,
+        }
+    }
+
+    /// Compara por monto; los precios sin monto van al final
+    pub fn cmp_amount(&self, other: &Price) -> Ordering {
+        match (self.amount, other.amount) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    /// Monto formateado con su moneda, o el texto original si no se pudo interpretar
+    pub fn formatted(&self) -> String {
+        match (&self.amount, &self.currency) {
+            (Some(amount), Some(currency)) => format!("{} {}", amount, currency),
+            (Some(amount), None) => amount.to_string(),
+            _ => self.raw.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+fn strip_from_prefix(text: &str) -> &str {
+    for prefix in FROM_PREFIXES {
+        if text.get(..prefix.len()).is_some_and(|p| p.eq_ignore_ascii_case(prefix)) {
+            let rest = &text[prefix.len()..];
+            // Sólo es prefijo si le sigue un separador ("ab" no debe comerse "abc")
+            if rest.starts_with(|c: char| !c.is_alphanumeric()) {
+                return rest.trim_start_matches([' ', ':']);
+            }
+        }
+    }
+    text
+}
+
+fn detect_currency(text: &str) -> Option<String> {
+    let upper = text.to_uppercase();
+
+    for code in CURRENCY_CODES {
+        if let Some(pos) = upper.find(code) {
+            let before = upper[..pos].chars().next_back();
+            let after = upper[pos + code.len()..].chars().next();
+            let bounded = |c: Option<char>| c.is_none_or(|c| !c.is_ascii_alphabetic());
+            if bounded(before) && bounded(after) {
+                return Some(code.to_string());
+            }
+        }
+    }
+
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(symbol, _)| upper.contains(&symbol.to_uppercase()))
+        .map(|(_, code)| code.to_string())
+}
+
+/// Toma la primera secuencia numérica, incluyendo separadores entre dígitos
+fn extract_number(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let start = chars.iter().position(|c| c.is_ascii_digit())?;
+    let mut number = String::new();
+
+    for (i, &c) in chars.iter().enumerate().skip(start) {
+        let next_is_digit = chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+        let accepted = if is_space(c) {
+            // Un espacio sólo agrupa miles si le siguen exactamente tres dígitos; si no,
+            // "1.299,99 3 cuotas" se leería como un único número
+            is_group_of_three(&chars[i + 1..])
+        } else {
+            c.is_ascii_digit() || (is_separator(c) && next_is_digit)
+        };
+        if !accepted {
+            break;
+        }
+        number.push(c);
+    }
+
+    Some(number)
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '.' | ',' | '\'')
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\u{a0}' | '\u{202f}')
+}
+
+fn is_group_of_three(rest: &[char]) -> bool {
+    rest.len() >= 3
+        && rest[..3].iter().all(|c| c.is_ascii_digit())
+        && rest.get(3).is_none_or(|c| !c.is_ascii_digit())
+}
+
+/// Convierte el número a formato decimal estándar según el locale o por heurística
+fn normalize_number(number: &str, locale: &PriceLocale) -> Option<Decimal> {
+    let decimal_separator = locale
+        .decimal_separator
+        .or_else(|| guess_decimal_separator(number, locale.thousands_separator));
+
+    let normalized: String = number
+        .chars()
+        .filter_map(|c| {
+            if c.is_ascii_digit() {
+                Some(c)
+            } else if Some(c) == decimal_separator {
+                Some('.')
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Decimal::from_str(&normalized).ok()
+}
+
+/// Deduce el separador decimal: el último separador es decimal si aparecen ambos,
+/// y un separador único seguido de exactamente tres dígitos se toma como de miles
+fn guess_decimal_separator(number: &str, thousands_separator: Option<char>) -> Option<char> {
+    let separators: Vec<(usize, char)> = number
+        .char_indices()
+        .filter(|(_, c)| matches!(c, '.' | ','))
+        .filter(|(_, c)| Some(*c) != thousands_separator)
+        .collect();
+
+    let (last_pos, last_char) = *separators.last()?;
+
+    if separators.iter().any(|(_, c)| *c != last_char) {
+        return Some(last_char);
+    }
+
+    if separators.len() > 1 {
+        return None;
+    }
+
+    if thousands_separator.is_some() {
+        return Some(last_char);
+    }
+
+    let digits_after = number[last_pos + 1..].chars().filter(|c| c.is_ascii_digit()).count();
+    if digits_after == 3 { None } else { Some(last_char) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(raw: &str) -> Option<Decimal> {
+        Price::parse(raw, &PriceLocale::default()).amount
+    }
+
+    fn dec(text: &str) -> Option<Decimal> {
+        Some(Decimal::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn parses_comma_decimal() {
+        assert_eq!(amount("1.299,99"), dec("1299.99"));
+    }
+
+    #[test]
+    fn parses_point_decimal() {
+        assert_eq!(amount("1,299.99"), dec("1299.99"));
+    }
+
+    #[test]
+    fn single_separator_before_three_digits_is_thousands() {
+        assert_eq!(amount("1.299"), dec("1299"));
+    }
+
+    #[test]
+    fn space_groups_thousands() {
+        assert_eq!(amount("1 299,99"), dec("1299.99"));
+        assert_eq!(amount("1\u{a0}299,99 €"), dec("1299.99"));
+    }
+
+    #[test]
+    fn swiss_format_with_dash() {
+        let price = Price::parse("CHF 1'299.–", &PriceLocale::default());
+        assert_eq!(price.amount, dec("1299"));
+        assert_eq!(price.currency.as_deref(), Some("CHF"));
+    }
+
+    #[test]
+    fn adjacent_numbers_are_not_merged() {
+        assert_eq!(amount("1.299,99 3 cuotas"), dec("1299.99"));
+        assert_eq!(amount("$ 25 12 cuotas"), dec("25"));
+    }
+
+    #[test]
+    fn locale_overrides_guess() {
+        let locale = PriceLocale {
+            decimal_separator: Some(','),
+            thousands_separator: Some('.'),
+            default_currency: Some("ARS".to_string()),
+        };
+        let price = Price::parse("$ 1.299", &locale);
+        assert_eq!(price.amount, dec("1299"));
+        assert_eq!(price.currency.as_deref(), Some("ARS"));
+    }
+
+    #[test]
+    fn strips_from_prefix_and_detects_symbol() {
+        let price = Price::parse("Desde US$ 19.90", &PriceLocale::default());
+        assert_eq!(price.amount, dec("19.90"));
+        assert_eq!(price.currency.as_deref(), Some("USD"));
+    }
+
+    #[test]
+    fn text_without_digits_has_no_amount() {
+        assert_eq!(amount("Consultar"), None);
+    }
+}
@@ -1,19 +1,82 @@
+use crate::models::Price;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Product {
     pub name: String,
-    pub price: String,
+    pub price: Price,
     pub url: String,
     pub image_url: String,
     pub store_name: String,
     pub description: Option<String>,
+    // Identificadores para reconocer el mismo producto en distintas tiendas
+    #[serde(default)]
+    pub gtin: Option<String>,
+    #[serde(default)]
+    pub sku: Option<String>,
+    #[serde(default)]
+    pub brand: Option<String>,
+    // Momento en que se extrajo; no existe en resultados guardados con versiones anteriores
+    #[serde(default)]
+    pub scraped_at: Option<DateTime<Utc>>,
+    // Campos personalizados de la tienda (valoración, stock, envío...) por nombre
+    #[serde(default)]
+    pub extra: BTreeMap<String, ExtraValue>,
+    // Consulta que encontró el producto; distingue los resultados de una búsqueda por lotes
+    #[serde(default)]
+    pub query: Option<String>,
+    // Página de resultados donde apareció; si `url` coincide con ella el producto no tenía enlace
+    #[serde(default)]
+    pub page_url: Option<String>,
+}
+
+/// Valor de un campo personalizado, ya convertido a su tipo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ExtraValue {
+    Text(String),
+    Number(Decimal),
+    /// Presencia de un elemento, como una etiqueta de "Sin stock"
+    Flag(bool),
+}
+
+impl ExtraValue {
+    pub fn as_number(&self) -> Option<Decimal> {
+        match self {
+            ExtraValue::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Orden natural: números por valor, textos sin distinguir mayúsculas
+    pub fn cmp_value(&self, other: &ExtraValue) -> Ordering {
+        match (self, other) {
+            (ExtraValue::Number(a), ExtraValue::Number(b)) => a.cmp(b),
+            (ExtraValue::Flag(a), ExtraValue::Flag(b)) => a.cmp(b),
+            _ => self.to_string().to_lowercase().cmp(&other.to_string().to_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for ExtraValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtraValue::Text(text) => write!(f, "{}", text),
+            ExtraValue::Number(number) => write!(f, "{}", number.normalize()),
+            ExtraValue::Flag(true) => write!(f, "Sí"),
+            ExtraValue::Flag(false) => write!(f, "No"),
+        }
+    }
 }
 
 impl Product {
     pub fn new(
         name: String,
-        price: String,
+        price: Price,
         url: String,
         image_url: String,
         store_name: String,
@@ -25,6 +88,13 @@ impl Product {
             image_url,
             store_name,
             description: None,
+            gtin: None,
+            sku: None,
+            brand: None,
+            scraped_at: Some(Utc::now()),
+            extra: BTreeMap::new(),
+            query: None,
+            page_url: None,
         }
     }
 
@@ -32,15 +102,4 @@ impl Product {
         self.description = Some(description);
         self
     }
-
-    // extraer el precio 
-    pub fn get_numeric_price(&self) -> f64 {
-        self.price
-            .chars()
-            .filter(|c| c.is_numeric() || *c == '.' || *c == ',')
-            .collect::<String>()
-            .replace(',', ".")
-            .parse()
-            .unwrap_or(0.0)
-    }
 }
\ No newline at end of file
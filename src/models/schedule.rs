@@ -0,0 +1,177 @@
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+
+/// Cuándo se repite una búsqueda programada
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleTrigger {
+    /// Cada tantos minutos desde la última ejecución
+    Interval { minutes: u64 },
+    /// Expresión cron de cinco campos ("0 9,18 * * 1-5"), en hora local
+    Cron { expression: String },
+}
+
+impl ScheduleTrigger {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ScheduleTrigger::Interval { minutes: 0 } => Err("El intervalo debe ser de al menos 1 minuto".to_string()),
+            ScheduleTrigger::Interval { .. } => Ok(()),
+            ScheduleTrigger::Cron { expression } => parse_cron(expression).map(|_| ()),
+        }
+    }
+
+    /// Primera ejecución posterior a `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+        match self {
+            ScheduleTrigger::Interval { minutes } => i64::try_from((*minutes).max(1))
+                .ok()
+                .and_then(TimeDelta::try_minutes)
+                .and_then(|interval| after.checked_add_signed(interval))
+                .ok_or_else(|| format!("El intervalo de {} minutos es demasiado largo", minutes)),
+            ScheduleTrigger::Cron { expression } => {
+                let cron = parse_cron(expression)?;
+                cron.find_next_occurrence(&after.with_timezone(&Local), false)
+                    .map(|next| next.with_timezone(&Utc))
+                    .map_err(|e| format!("Sin próxima ejecución para \"{}\": {}", expression, e))
+            }
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            ScheduleTrigger::Interval { minutes } if minutes % 60 == 0 => format!("cada {} h", minutes / 60),
+            ScheduleTrigger::Interval { minutes } => format!("cada {} min", minutes),
+            ScheduleTrigger::Cron { expression } => format!("cron \"{}\"", expression),
+        }
+    }
+}
+
+fn parse_cron(expression: &str) -> Result<Cron, String> {
+    Cron::new(expression.trim())
+        .parse()
+        .map_err(|e| format!("Expresión cron inválida \"{}\": {}", expression, e))
+}
+
+/// Búsqueda guardada que se repite sola; se configura en config.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledSearch {
+    // Identifica la programación en el estado y en la carpeta de instantáneas
+    pub name: String,
+    pub query: String,
+    // Nombres de las tiendas; vacío usa las habilitadas en el momento de ejecutar
+    #[serde(default)]
+    pub stores: Vec<String>,
+    pub trigger: ScheduleTrigger,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ScheduledSearch {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("La programación necesita un nombre".to_string());
+        }
+        if self.query.trim().is_empty() {
+            return Err("La programación necesita un término de búsqueda".to_string());
+        }
+        self.trigger.validate()
+    }
+
+    /// Próxima ejecución según la última. Sin ejecuciones previas, un intervalo arranca
+    /// en seguida y un cron espera a su próxima hora. Si la app estuvo cerrada, la
+    /// ejecución perdida se hace una sola vez al volver.
+    pub fn next_run(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+        match (last_run, &self.trigger) {
+            (Some(last_run), trigger) => trigger.next_after(last_run),
+            (None, ScheduleTrigger::Interval { .. }) => Ok(now),
+            (None, trigger) => trigger.next_after(now),
+        }
+    }
+}
+
+/// Resultado de la última ejecución de una programación
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub product_count: usize,
+    // Errores por tienda, o el motivo por el que no se pudo buscar
+    pub errors: Vec<String>,
+    // Archivo con los productos obtenidos
+    pub snapshot: Option<String>,
+}
+
+impl ScheduleRun {
+    pub fn succeeded(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn schedule(trigger: ScheduleTrigger) -> ScheduledSearch {
+        ScheduledSearch {
+            name: "Ofertas".to_string(),
+            query: "taladro".to_string(),
+            stores: Vec::new(),
+            trigger,
+            enabled: true,
+        }
+    }
+
+    fn local(hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2026, 10, 14, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn interval_starts_right_away_and_then_waits() {
+        let hourly = schedule(ScheduleTrigger::Interval { minutes: 60 });
+        let now = local(8, 0);
+        assert_eq!(hourly.next_run(None, now).unwrap(), now);
+        assert_eq!(hourly.next_run(Some(local(7, 30)), now).unwrap(), local(8, 30));
+    }
+
+    #[test]
+    fn missed_interval_runs_once_when_back() {
+        let hourly = schedule(ScheduleTrigger::Interval { minutes: 60 });
+        let now = local(20, 0);
+        // La última ejecución fue por la mañana: toca ya, una sola vez
+        let next = hourly.next_run(Some(local(8, 0)), now).unwrap();
+        assert!(next <= now);
+        assert_eq!(hourly.next_run(Some(now), now).unwrap(), local(21, 0));
+    }
+
+    #[test]
+    fn cron_waits_for_its_next_local_time() {
+        let morning = schedule(ScheduleTrigger::Cron {
+            expression: "0 9 * * *".to_string(),
+        });
+        assert_eq!(morning.next_run(None, local(8, 0)).unwrap(), local(9, 0));
+        assert_eq!(morning.next_run(Some(local(9, 0)), local(10, 0)).unwrap(), local(9, 0) + TimeDelta::days(1));
+    }
+
+    #[test]
+    fn huge_interval_is_an_error() {
+        let never = schedule(ScheduleTrigger::Interval { minutes: u64::MAX });
+        assert!(never.next_run(Some(local(8, 0)), local(9, 0)).is_err());
+        let ages = schedule(ScheduleTrigger::Interval { minutes: i64::MAX as u64 / 60_000 });
+        assert!(ages.next_run(Some(local(8, 0)), local(9, 0)).is_err());
+    }
+
+    #[test]
+    fn invalid_triggers_are_rejected() {
+        assert!(schedule(ScheduleTrigger::Interval { minutes: 0 }).validate().is_err());
+        let cron = schedule(ScheduleTrigger::Cron {
+            expression: "todos los días".to_string(),
+        });
+        assert!(cron.validate().is_err());
+        assert!(cron.next_run(None, local(8, 0)).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Entradas sin fijar que se conservan; las fijadas no cuentan para el límite
+const MAX_UNPINNED_ENTRIES: usize = 200;
+
+/// Una búsqueda realizada
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    // Nombres de las tiendas en las que se buscó
+    pub stores: Vec<String>,
+    pub searched_at: DateTime<Utc>,
+    pub result_count: usize,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Historial de búsquedas, de la más reciente a la más antigua
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchHistory {
+    pub entries: Vec<SearchHistoryEntry>,
+}
+
+impl SearchHistory {
+    /// Añade una búsqueda al principio y descarta las más antiguas sin fijar
+    pub fn record(&mut self, entry: SearchHistoryEntry) {
+        self.entries.insert(0, entry);
+
+        let mut unpinned = 0;
+        self.entries.retain(|entry| {
+            if entry.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= MAX_UNPINNED_ENTRIES
+        });
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<SearchHistoryEntry> {
+        (index < self.entries.len()).then(|| self.entries.remove(index))
+    }
+
+    pub fn toggle_pin(&mut self, index: usize) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.pinned = !entry.pinned;
+        }
+    }
+
+    /// Consulta más reciente, para recuperar el campo de búsqueda al abrir la app
+    pub fn last_query(&self) -> Option<&str> {
+        self.entries.first().map(|entry| entry.query.as_str())
+    }
+
+    /// Consultas distintas que contienen el texto escrito, primero las fijadas y luego
+    /// las más recientes
+    pub fn suggestions(&self, typed: &str, limit: usize) -> Vec<String> {
+        let typed = typed.trim().to_lowercase();
+        if typed.is_empty() {
+            return Vec::new();
+        }
+
+        let pinned = self.entries.iter().filter(|entry| entry.pinned);
+        let recent = self.entries.iter().filter(|entry| !entry.pinned);
+
+        let mut seen = HashSet::new();
+        pinned
+            .chain(recent)
+            .map(|entry| entry.query.trim())
+            .filter(|query| {
+                let lower = query.to_lowercase();
+                lower != typed && lower.contains(&typed)
+            })
+            .filter(|query| seen.insert(query.to_lowercase()))
+            .take(limit)
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(query: &str, pinned: bool) -> SearchHistoryEntry {
+        SearchHistoryEntry {
+            query: query.to_string(),
+            stores: vec!["Tienda".to_string()],
+            searched_at: Utc::now(),
+            result_count: 3,
+            duration_ms: 120,
+            pinned,
+        }
+    }
+
+    #[test]
+    fn newest_search_goes_first() {
+        let mut history = SearchHistory::default();
+        history.record(entry("taladro", false));
+        history.record(entry("sierra", false));
+        assert_eq!(history.last_query(), Some("sierra"));
+        assert_eq!(history.entries[1].query, "taladro");
+    }
+
+    #[test]
+    fn only_unpinned_entries_count_for_the_limit() {
+        let mut history = SearchHistory::default();
+        history.record(entry("fijada", true));
+        for i in 0..MAX_UNPINNED_ENTRIES + 5 {
+            history.record(entry(&format!("consulta {}", i), false));
+        }
+
+        assert_eq!(history.entries.len(), MAX_UNPINNED_ENTRIES + 1);
+        assert!(history.entries.iter().any(|e| e.query == "fijada"));
+        // Se descartan las más antiguas
+        assert!(!history.entries.iter().any(|e| e.query == "consulta 4"));
+        assert!(history.entries.iter().any(|e| e.query == "consulta 5"));
+    }
+
+    #[test]
+    fn suggestions_are_deduplicated_and_pinned_first() {
+        let mut history = SearchHistory::default();
+        history.record(entry("Taladro percutor", false));
+        history.record(entry("taladro percutor ", false));
+        history.record(entry("taladro inalámbrico", false));
+        history.record(entry("brocas", false));
+        history.record(entry("taladro bosch", true));
+
+        assert_eq!(
+            history.suggestions("TALADRO", 10),
+            ["taladro bosch", "taladro inalámbrico", "taladro percutor"]
+        );
+        assert_eq!(history.suggestions("taladro", 1), ["taladro bosch"]);
+        assert!(history.suggestions("  ", 10).is_empty());
+        // Lo que ya está escrito no se sugiere
+        assert!(history.suggestions("brocas", 10).is_empty());
+    }
+
+    #[test]
+    fn remove_and_toggle_pin_ignore_bad_indexes() {
+        let mut history = SearchHistory::default();
+        history.record(entry("taladro", false));
+
+        history.toggle_pin(0);
+        history.toggle_pin(5);
+        assert!(history.entries[0].pinned);
+
+        assert_eq!(history.remove(5), None);
+        assert_eq!(history.remove(0).map(|e| e.query), Some("taladro".to_string()));
+        assert!(history.entries.is_empty());
+    }
+}
@@ -1,4 +1,9 @@
+use crate::models::field_spec::{self, CustomField, FieldSpec};
+use crate::models::PriceLocale;
+use crate::scraping::{ProxyConfig, RateLimitConfig, RetryPolicy};
+use reqwest::header::{HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoreConfig {
@@ -10,12 +15,136 @@ pub struct StoreConfig {
 
     
     pub product_container_selector: String,
-    pub name_selector: String,
-    pub price_selector: String,
-    pub image_selector: String,
-    pub link_selector: String,
-    pub description_selector: Option<String>,
+    // Campos dentro de cada contenedor; en disco aceptan también un selector como texto
+    #[serde(deserialize_with = "field_spec::deserialize_text")]
+    pub name_selector: FieldSpec,
+    #[serde(deserialize_with = "field_spec::deserialize_text")]
+    pub price_selector: FieldSpec,
+    #[serde(deserialize_with = "field_spec::deserialize_src")]
+    pub image_selector: FieldSpec,
+    #[serde(deserialize_with = "field_spec::deserialize_href")]
+    pub link_selector: FieldSpec,
+    #[serde(default, deserialize_with = "field_spec::deserialize_optional_text")]
+    pub description_selector: Option<FieldSpec>,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
     pub enabled: bool,
+
+    // Ajustes HTTP propios de la tienda; los vacíos usan los de AppConfig
+    #[serde(default)]
+    pub http: HttpOverrides,
+
+    #[serde(default)]
+    pub pagination: PaginationConfig,
+
+    #[serde(default)]
+    pub price_locale: PriceLocale,
+
+    // Sólo para sitios que dieron permiso explícito de rastreo
+    #[serde(default)]
+    pub ignore_robots: bool,
+
+    #[serde(default)]
+    pub extraction_mode: ExtractionMode,
+}
+
+/// De dónde se sacan los datos de los productos
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExtractionMode {
+    /// Sólo selectores CSS
+    Css,
+    /// Sólo datos estructurados schema.org (JSON-LD / microdata)
+    StructuredData,
+    /// Selectores CSS y, si no encuentran nada, datos estructurados
+    #[default]
+    CssWithFallback,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaginationMode {
+    /// Sólo se consulta la primera página
+    #[default]
+    None,
+    /// El patrón de búsqueda usa {page} y/o {offset}
+    UrlPattern,
+    /// Se sigue el enlace de "página siguiente"
+    NextLink,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaginationConfig {
+    pub mode: PaginationMode,
+    pub max_pages: usize,
+    // Número de la primera página para {page} (normalmente 0 o 1)
+    pub first_page: usize,
+    // Productos por página, usado para calcular {offset}
+    pub page_size: usize,
+    pub next_page_selector: String,
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self {
+            mode: PaginationMode::None,
+            max_pages: 5,
+            first_page: 1,
+            page_size: 20,
+            next_page_selector: String::new(),
+        }
+    }
+}
+
+impl PaginationConfig {
+    /// Cantidad máxima de páginas a recorrer según el modo
+    pub fn page_limit(&self) -> usize {
+        match self.mode {
+            PaginationMode::None => 1,
+            _ => self.max_pages.max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpOverrides {
+    pub user_agent: Option<String>,
+    pub request_delay_ms: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+    pub max_redirects: Option<usize>,
+    pub retry: Option<RetryPolicy>,
+    // Límite de velocidad propio; sin él se usa el global
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+    // Proxy propio, o modo directo para saltarse el global
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+    // Cabeceras fijas, como Accept-Language, que se envían en cada petición
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    // Cookies con las que empieza el tarro de la tienda, como la región o el idioma
+    #[serde(default)]
+    pub cookies: BTreeMap<String, String>,
+}
+
+impl HttpOverrides {
+    /// Comprueba que las cabeceras y cookies se puedan enviar y que el proxy sea válido
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in &self.headers {
+            HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| format!("Nombre de cabecera inválido: \"{}\"", name))?;
+            HeaderValue::from_str(value).map_err(|_| format!("Valor inválido en la cabecera \"{}\"", name))?;
+        }
+        for name in self.cookies.keys() {
+            let valid = !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || "=;,\"".contains(c));
+            if !valid {
+                return Err(format!("Nombre de cookie inválido: \"{}\"", name));
+            }
+        }
+        match &self.proxy {
+            Some(proxy) => proxy.validate(),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Default for StoreConfig {
@@ -25,12 +154,18 @@ impl Default for StoreConfig {
             base_url: String::new(),
             search_url_pattern: "{base_url}/search?q={query}".to_string(),
             product_container_selector: String::new(),
-            name_selector: String::new(),
-            price_selector: String::new(),
-            image_selector: String::new(),
-            link_selector: String::new(),
+            name_selector: FieldSpec::text(""),
+            price_selector: FieldSpec::text(""),
+            image_selector: FieldSpec::attribute("", "src"),
+            link_selector: FieldSpec::attribute("", "href"),
             description_selector: None,
+            custom_fields: Vec::new(),
             enabled: true,
+            http: HttpOverrides::default(),
+            pagination: PaginationConfig::default(),
+            price_locale: PriceLocale::default(),
+            ignore_robots: false,
+            extraction_mode: ExtractionMode::default(),
         }
     }
 }
@@ -45,17 +180,45 @@ impl StoreConfig {
     }
 
     pub fn build_search_url(&self, query: &str) -> String {
+        self.build_page_url(query, 0)
+    }
+
+    /// Construye la URL de una página de resultados (`page_index` empieza en 0)
+    pub fn build_page_url(&self, query: &str, page_index: usize) -> String {
+        let page = self.pagination.first_page + page_index;
+        let offset = page_index * self.pagination.page_size;
+
         self.search_url_pattern
             .replace("{base_url}", &self.base_url)
             .replace("{query}", query)
+            .replace("{page}", &page.to_string())
+            .replace("{offset}", &offset.to_string())
+    }
+
+    /// Especificaciones de los campos con su nombre para mostrar, personalizados incluidos
+    pub fn field_specs(&self) -> impl Iterator<Item = (&str, &FieldSpec)> {
+        [
+            ("Nombre", &self.name_selector),
+            ("Precio", &self.price_selector),
+            ("Imagen", &self.image_selector),
+            ("Enlace", &self.link_selector),
+        ]
+        .into_iter()
+        .chain(self.description_selector.iter().map(|spec| ("Descripción", spec)))
+        .chain(self.custom_fields.iter().map(|field| (field.name.as_str(), &field.spec)))
     }
 
     pub fn is_valid(&self) -> bool {
+        let has_selectors = !self.product_container_selector.is_empty()
+            && !self.name_selector.is_empty()
+            && !self.price_selector.is_empty();
+
+        // Con datos estructurados no hacen falta selectores CSS
         !self.name.is_empty()
+            && self.field_specs().all(|(_, spec)| spec.validate().is_ok())
+            && self.http.validate().is_ok()
             && !self.base_url.is_empty()
-            && !self.product_container_selector.is_empty()
-            && !self.name_selector.is_empty()
-            && !self.price_selector.is_empty()
+            && (has_selectors || self.extraction_mode == ExtractionMode::StructuredData)
     }
 }
 
@@ -74,12 +237,18 @@ impl Default for StoreManager {
                     base_url: "https://ejemplo.com".to_string(),
                     search_url_pattern: "{base_url}/search?q={query}".to_string(),
                     product_container_selector: ".product-item".to_string(),
-                    name_selector: ".product-name".to_string(),
-                    price_selector: ".price".to_string(),
-                    image_selector: ".product-image img".to_string(),
-                    link_selector: "a".to_string(),
-                    description_selector: Some(".description".to_string()),
+                    name_selector: FieldSpec::text(".product-name"),
+                    price_selector: FieldSpec::text(".price"),
+                    image_selector: FieldSpec::attribute(".product-image img", "src"),
+                    link_selector: FieldSpec::attribute("a", "href"),
+                    description_selector: Some(FieldSpec::text(".description")),
+                    custom_fields: Vec::new(),
                     enabled: true,
+                    http: HttpOverrides::default(),
+                    pagination: PaginationConfig::default(),
+                    price_locale: PriceLocale::default(),
+                    ignore_robots: false,
+                    extraction_mode: ExtractionMode::default(),
                 },
             ],
         }
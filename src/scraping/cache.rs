@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Configuración de la caché de respuestas HTTP. Está desactivada por defecto: con ella
+/// las búsquedas programadas y las alertas pueden evaluar precios de hace `ttl_secs`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub ttl_secs: u64,
+    pub max_size_mb: u64,
+    // Sólo sirve respuestas de la caché, sin tocar la red
+    pub offline: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 15 * 60,
+            max_size_mb: 100,
+            offline: false,
+        }
+    }
+}
+
+/// Respuesta guardada en disco
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    pub stored_at: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+impl CachedResponse {
+    pub fn new(url: &str, etag: Option<String>, last_modified: Option<String>, body: String) -> Self {
+        Self {
+            url: url.to_string(),
+            stored_at: now_secs(),
+            etag,
+            last_modified,
+            body,
+        }
+    }
+
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now_secs().saturating_sub(self.stored_at))
+    }
+}
+
+/// Caché de respuestas en disco, con TTL y límite de tamaño
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_size_bytes: u64,
+    offline: bool,
+    // Serializa escrituras y desalojos entre hilos
+    write_lock: Mutex<()>,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>, config: &CacheConfig) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: Duration::from_secs(config.ttl_secs),
+            max_size_bytes: config.max_size_mb.saturating_mul(1024 * 1024),
+            offline: config.offline,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Clave de caché: la URL más las cabeceras que cambian la respuesta
+    pub fn key(url: &str, headers: &[(&str, &str)]) -> String {
+        let mut hash = Fnv1a::new();
+        hash.write(url.as_bytes());
+        for (name, value) in headers {
+            hash.write(b"\n");
+            hash.write(name.to_lowercase().as_bytes());
+            hash.write(b":");
+            hash.write(value.as_bytes());
+        }
+        format!("{:016x}", hash.finish())
+    }
+
+    /// Entrada guardada para la clave; se descarta si es de otra URL (colisión del hash)
+    pub fn get(&self, key: &str, url: &str) -> Option<CachedResponse> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let entry: CachedResponse = serde_json::from_str(&content).ok()?;
+        (entry.url == url).then_some(entry)
+    }
+
+    pub fn is_fresh(&self, entry: &CachedResponse) -> bool {
+        entry.age() < self.ttl
+    }
+
+    pub fn store(&self, key: &str, response: &CachedResponse) -> Result<(), String> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Error al crear la caché: {}", e))?;
+        let json = serde_json::to_string(response).map_err(|e| format!("Error al serializar la caché: {}", e))?;
+        fs::write(self.entry_path(key), json).map_err(|e| format!("Error al escribir la caché: {}", e))?;
+
+        evict_oldest(&self.dir, self.max_size_bytes);
+        Ok(())
+    }
+
+    /// Marca una entrada como recién validada (respuesta 304)
+    pub fn touch(&self, key: &str, mut entry: CachedResponse) -> Result<CachedResponse, String> {
+        entry.stored_at = now_secs();
+        self.store(key, &entry)?;
+        Ok(entry)
+    }
+
+    /// Borra todas las entradas
+    pub fn clear(&self) -> Result<(), String> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir).map_err(|e| format!("Error al vaciar la caché: {}", e))?;
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+/// Elimina los archivos más antiguos del directorio hasta quedar bajo el límite de tamaño
+pub(crate) fn evict_oldest(dir: &Path, max_size_bytes: u64) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries: Vec<(PathBuf, u64, SystemTime)> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            Some((entry.path(), metadata.len(), modified))
+        })
+        .collect();
+
+    let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+    if total <= max_size_bytes {
+        return;
+    }
+
+    entries.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in entries {
+        if total <= max_size_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(size);
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Hash FNV-1a de 64 bits: estable entre versiones de Rust, a diferencia de DefaultHasher
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(name: &str, config: CacheConfig) -> ResponseCache {
+        let dir = std::env::temp_dir().join(format!("yam-webs-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        ResponseCache::new(dir, &config)
+    }
+
+    fn response(url: &str, body: &str) -> CachedResponse {
+        CachedResponse::new(url, Some("\"v1\"".to_string()), None, body.to_string())
+    }
+
+    const URL: &str = "https://tienda.example/buscar?q=taladro";
+
+    #[test]
+    fn key_depends_on_url_and_headers() {
+        let es = ResponseCache::key(URL, &[("Accept-Language", "es")]);
+        assert_eq!(es, ResponseCache::key(URL, &[("accept-language", "es")]));
+        assert_ne!(es, ResponseCache::key(URL, &[("accept-language", "en")]));
+        assert_ne!(es, ResponseCache::key(URL, &[]));
+        assert_ne!(es, ResponseCache::key("https://tienda.example/buscar?q=lijadora", &[("accept-language", "es")]));
+    }
+
+    #[test]
+    fn entries_are_fresh_until_the_ttl() {
+        let cache = cache("ttl", CacheConfig::default());
+        let mut entry = response(URL, "<html></html>");
+        assert!(cache.is_fresh(&entry));
+
+        entry.stored_at -= 15 * 60;
+        assert!(!cache.is_fresh(&entry));
+    }
+
+    #[test]
+    fn stored_entry_is_only_returned_for_its_url() {
+        let cache = cache("url", CacheConfig::default());
+        let key = ResponseCache::key(URL, &[]);
+        cache.store(&key, &response(URL, "taladros")).unwrap();
+
+        assert_eq!(cache.get(&key, URL).unwrap().body, "taladros");
+        // Misma clave con otra URL: colisión del hash, no se sirve
+        assert!(cache.get(&key, "https://tienda.example/otra").is_none());
+        assert!(cache.get("0000000000000000", URL).is_none());
+    }
+
+    #[test]
+    fn touch_renews_the_entry() {
+        let cache = cache("touch", CacheConfig::default());
+        let key = ResponseCache::key(URL, &[]);
+        let mut stale = response(URL, "taladros");
+        stale.stored_at -= 60 * 60;
+        cache.store(&key, &stale).unwrap();
+
+        let renewed = cache.touch(&key, stale).unwrap();
+        assert!(cache.is_fresh(&renewed));
+        assert!(cache.is_fresh(&cache.get(&key, URL).unwrap()));
+        assert_eq!(renewed.etag.as_deref(), Some("\"v1\""));
+    }
+
+    #[test]
+    fn oldest_entries_are_evicted_over_the_limit() {
+        let dir = std::env::temp_dir().join(format!("yam-webs-cache-evict-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        for (i, name) in ["a", "b", "c"].iter().enumerate() {
+            let path = dir.join(name);
+            fs::write(&path, [0u8; 100]).unwrap();
+            let modified = UNIX_EPOCH + Duration::from_secs(1_000 + i as u64);
+            fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        }
+
+        evict_oldest(&dir, 250);
+        let mut left: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["b", "c"]);
+    }
+
+    #[test]
+    fn huge_size_limit_does_not_overflow() {
+        let config = CacheConfig {
+            max_size_mb: u64::MAX,
+            ..CacheConfig::default()
+        };
+        assert_eq!(cache("huge", config).max_size_bytes, u64::MAX);
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Utc};
+use cookie_store::{CookieExpiration, CookieStore, RawCookie};
+use crate::utils::safe_file_name;
+use reqwest::Url;
+use reqwest_cookie_store::CookieStoreMutex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Cookie guardada en el tarro de una tienda, tal como se muestra en la interfaz
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredCookie {
+    pub domain: String,
+    pub path: String,
+    pub name: String,
+    pub value: String,
+    // Sin fecha es una cookie de sesión
+    pub expires: Option<DateTime<Utc>>,
+}
+
+/// Tarros de cookies por tienda, persistidos en un JSON por tienda entre sesiones
+pub struct CookieJars {
+    dir: PathBuf,
+    jars: Mutex<HashMap<String, Arc<CookieStoreMutex>>>,
+}
+
+impl CookieJars {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            jars: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Tarro de la tienda, cargándolo del disco la primera vez
+    pub fn jar(&self, store_name: &str) -> Arc<CookieStoreMutex> {
+        let mut jars = self.jars.lock().unwrap_or_else(|e| e.into_inner());
+        let jar = jars
+            .entry(store_name.to_string())
+            .or_insert_with(|| Arc::new(CookieStoreMutex::new(self.load(store_name))));
+        Arc::clone(jar)
+    }
+
+    /// Añade las cookies iniciales de la tienda que todavía no estén en el tarro, así
+    /// que las que fija el sitio después tienen prioridad
+    pub fn seed(&self, store_name: &str, url: &str, cookies: &BTreeMap<String, String>) {
+        let Ok(url) = Url::parse(url) else {
+            return;
+        };
+        let Some(host) = url.host_str() else {
+            return;
+        };
+
+        let jar = self.jar(store_name);
+        let mut store = jar.lock().unwrap_or_else(|e| e.into_inner());
+        for (name, value) in cookies {
+            if store.get(host, "/", name).is_none() {
+                let cookie = RawCookie::build((name.clone(), value.clone())).path("/").build();
+                if let Err(e) = store.insert_raw(&cookie, &url) {
+                    eprintln!("Cookie inicial \"{}\" inválida para {}: {}", name, store_name, e);
+                }
+            }
+        }
+    }
+
+    /// Cookies del tarro que se enviarían a la URL, ordenadas por nombre
+    pub fn request_cookies(&self, store_name: &str, url: &str) -> Vec<(String, String)> {
+        let Ok(url) = Url::parse(url) else {
+            return Vec::new();
+        };
+
+        let jar = self.jar(store_name);
+        let store = jar.lock().unwrap_or_else(|e| e.into_inner());
+        let mut cookies: Vec<(String, String)> = store
+            .get_request_values(&url)
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        cookies.sort();
+        cookies
+    }
+
+    /// Cookies vigentes del tarro, ordenadas por dominio y nombre
+    pub fn list(&self, store_name: &str) -> Vec<StoredCookie> {
+        let jar = self.jar(store_name);
+        let store = jar.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut cookies: Vec<StoredCookie> = store
+            .iter_unexpired()
+            .map(|cookie| StoredCookie {
+                domain: cookie.domain.as_cow().map(|d| d.into_owned()).unwrap_or_default(),
+                path: String::from(&*cookie.path),
+                name: cookie.name().to_string(),
+                value: cookie.value().to_string(),
+                expires: match &cookie.expires {
+                    CookieExpiration::AtUtc(at) => DateTime::from_timestamp(at.unix_timestamp(), 0),
+                    CookieExpiration::SessionEnd => None,
+                },
+            })
+            .collect();
+        cookies.sort_by(|a, b| (&a.domain, &a.name).cmp(&(&b.domain, &b.name)));
+        cookies
+    }
+
+    pub fn remove(&self, store_name: &str, cookie: &StoredCookie) -> Result<(), String> {
+        {
+            let jar = self.jar(store_name);
+            let mut store = jar.lock().unwrap_or_else(|e| e.into_inner());
+            store.remove(&cookie.domain, &cookie.path, &cookie.name);
+        }
+        self.save(store_name)
+    }
+
+    /// Vacía el tarro y borra su archivo
+    pub fn clear(&self, store_name: &str) -> Result<(), String> {
+        {
+            let jar = self.jar(store_name);
+            jar.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+
+        let path = self.path_for(store_name);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Error al borrar las cookies de {}: {}", store_name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Pasa el tarro (en memoria y en disco) al nuevo nombre de la tienda, para no perder
+    /// las cookies al renombrarla. Nunca sustituye cookies que ya tenga el nombre nuevo.
+    pub fn rename(&self, old_name: &str, new_name: &str) -> Result<(), String> {
+        if old_name == new_name {
+            return Ok(());
+        }
+
+        let mut jars = self.jars.lock().unwrap_or_else(|e| e.into_inner());
+        let new_path = self.path_for(new_name);
+        let new_in_use = jars
+            .get(new_name)
+            .is_some_and(|jar| jar.lock().unwrap_or_else(|e| e.into_inner()).iter_any().next().is_some());
+        if new_in_use || new_path.exists() {
+            return Err(format!("Ya hay cookies guardadas para {}; bórralas antes de renombrar", new_name));
+        }
+
+        let old_path = self.path_for(old_name);
+        if old_path.exists() {
+            fs::rename(&old_path, &new_path)
+                .map_err(|e| format!("Error al mover las cookies de {} a {}: {}", old_name, new_name, e))?;
+        }
+
+        // Un tarro vacío del nombre nuevo se descarta; si el viejo no estaba cargado, se
+        // leerá del archivo movido
+        jars.remove(new_name);
+        if let Some(jar) = jars.remove(old_name) {
+            jars.insert(new_name.to_string(), jar);
+        }
+        Ok(())
+    }
+
+    /// Guarda el tarro, incluidas las cookies de sesión: muchas tiendas guardan la región
+    /// elegida en una cookie sin caducidad
+    pub fn save(&self, store_name: &str) -> Result<(), String> {
+        let jar = self.jar(store_name);
+        let store = jar.lock().unwrap_or_else(|e| e.into_inner());
+
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Error al crear la carpeta de cookies: {}", e))?;
+        let mut content = Vec::new();
+        cookie_store::serde::json::save_incl_expired_and_nonpersistent(&store, &mut content)
+            .map_err(|e| format!("Error al serializar las cookies de {}: {}", store_name, e))?;
+        fs::write(self.path_for(store_name), content)
+            .map_err(|e| format!("Error al guardar las cookies de {}: {}", store_name, e))
+    }
+
+    fn load(&self, store_name: &str) -> CookieStore {
+        let path = self.path_for(store_name);
+        match fs::File::open(&path) {
+            Ok(file) => match cookie_store::serde::json::load(BufReader::new(file)) {
+                Ok(store) => store,
+                Err(e) => {
+                    eprintln!("Error al leer las cookies de {}: {}", store_name, e);
+                    CookieStore::default()
+                }
+            },
+            Err(_) => CookieStore::default(),
+        }
+    }
+
+    fn path_for(&self, store_name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", safe_file_name(store_name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jars(name: &str) -> CookieJars {
+        let dir = std::env::temp_dir().join(format!("yam-webs-cookies-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        CookieJars::new(dir)
+    }
+
+    fn seeded(jars: &CookieJars, store_name: &str) {
+        let cookies = BTreeMap::from([("region".to_string(), "ar".to_string())]);
+        jars.seed(store_name, "https://tienda.example/", &cookies);
+    }
+
+    #[test]
+    fn request_cookies_are_the_ones_sent_to_the_url() {
+        let jars = jars("request");
+        seeded(&jars, "Tienda");
+        assert_eq!(
+            jars.request_cookies("Tienda", "https://tienda.example/buscar"),
+            [("region".to_string(), "ar".to_string())]
+        );
+        assert!(jars.request_cookies("Tienda", "https://otra.example/").is_empty());
+    }
+
+    #[test]
+    fn rename_keeps_the_cookies() {
+        let jars = jars("rename");
+        seeded(&jars, "Tienda");
+        jars.save("Tienda").unwrap();
+
+        jars.rename("Tienda", "Tienda AR").unwrap();
+        assert_eq!(jars.list("Tienda AR").len(), 1);
+        assert!(jars.list("Tienda").is_empty());
+
+        // Otra instancia lo lee del archivo renombrado
+        let reloaded = CookieJars::new(jars.dir.clone());
+        assert_eq!(reloaded.list("Tienda AR").len(), 1);
+        assert!(!jars.path_for("Tienda").exists());
+    }
+
+    #[test]
+    fn rename_never_replaces_other_cookies() {
+        let jars = jars("rename-taken");
+        seeded(&jars, "Tienda");
+        seeded(&jars, "Tienda_AR");
+        jars.save("Tienda_AR").unwrap();
+
+        assert!(jars.rename("Tienda", "Tienda_AR").is_err());
+        assert_eq!(jars.list("Tienda").len(), 1);
+        assert_eq!(jars.list("Tienda_AR").len(), 1);
+
+        // Sólo en memoria también cuenta
+        seeded(&jars, "Otra");
+        assert!(jars.rename("Tienda", "Otra").is_err());
+
+        // Un tarro vacío no lo impide
+        assert!(jars.list("Nueva").is_empty());
+        jars.rename("Tienda", "Nueva").unwrap();
+        assert_eq!(jars.list("Nueva").len(), 1);
+    }
+
+    #[test]
+    fn similar_names_use_different_files() {
+        let jars = jars("names");
+        assert_ne!(jars.path_for("Tienda AR"), jars.path_for("Tienda_AR"));
+        assert_ne!(jars.path_for("Tienda AR"), jars.path_for("Tienda/AR"));
+    }
+}
@@ -0,0 +1,346 @@
+use crate::models::{Product, StoreConfig};
+use crate::scraping::{ScrapeError, WebScraper};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
+
+/// Eventos que el motor envía mientras avanza una búsqueda
+#[derive(Debug)]
+pub enum SearchEvent {
+    /// Una tienda comenzó a procesarse
+    StoreStarted { store_name: String },
+    /// Una tienda terminó, con sus productos o el error
+    StoreFinished {
+        store_name: String,
+        result: Result<Vec<Product>, ScrapeError>,
+    },
+    /// En un lote, empieza la consulta número `index`
+    QueryStarted { index: usize, query: String },
+    /// En un lote, todas las tiendas terminaron para la consulta número `index`
+    QueryFinished { index: usize },
+    /// Todas las tiendas terminaron
+    Finished,
+}
+
+type Notify = Arc<dyn Fn() + Send + Sync>;
+
+/// Motor de búsqueda que consulta todas las tiendas en paralelo sobre tokio
+pub struct SearchEngine {
+    runtime: Runtime,
+    scraper: Arc<WebScraper>,
+}
+
+impl SearchEngine {
+    pub fn new(scraper: Arc<WebScraper>) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("yam-webs-search")
+            .build()
+            .expect("No se pudo crear el runtime de tokio");
+
+        Self {
+            runtime,
+            scraper,
+        }
+    }
+
+    pub fn scraper(&self) -> &Arc<WebScraper> {
+        &self.scraper
+    }
+
+    /// Lanza la búsqueda en segundo plano y devuelve un handle para leer el progreso.
+    /// `notify` se llama cada vez que hay un evento nuevo (por ejemplo, para repintar la UI).
+    pub fn start_search<F>(&self, query: &str, stores: Vec<StoreConfig>, notify: F) -> SearchHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let total_stores = stores.len();
+        let query = query.to_string();
+        let scraper = Arc::clone(&self.scraper);
+        let notify: Notify = Arc::new(notify);
+
+        self.runtime.spawn(async move {
+            search_stores(&query, &stores, scraper, &sender, &notify).await;
+            send_event(&sender, &*notify, SearchEvent::Finished);
+        });
+
+        SearchHandle {
+            receiver,
+            total_stores,
+        }
+    }
+
+    /// Lanza varias consultas una detrás de otra sobre las mismas tiendas, esperando
+    /// `delay` entre consultas. Si se descarta el handle, el lote se detiene al terminar
+    /// la consulta en curso.
+    pub fn start_batch<F>(&self, queries: Vec<String>, stores: Vec<StoreConfig>, delay: Duration, notify: F) -> SearchHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let total_stores = stores.len();
+        let scraper = Arc::clone(&self.scraper);
+        let notify: Notify = Arc::new(notify);
+
+        self.runtime.spawn(async move {
+            let total = queries.len();
+            for (index, query) in queries.into_iter().enumerate() {
+                let event = SearchEvent::QueryStarted {
+                    index,
+                    query: query.clone(),
+                };
+                if !send_event(&sender, &*notify, event) {
+                    break;
+                }
+
+                search_stores(&query, &stores, Arc::clone(&scraper), &sender, &notify).await;
+                send_event(&sender, &*notify, SearchEvent::QueryFinished { index });
+
+                if index + 1 < total && !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+
+            // Igual que en search_stores, el Arc no se libera en el contexto async
+            let _ = tokio::task::spawn_blocking(move || drop(scraper)).await;
+            send_event(&sender, &*notify, SearchEvent::Finished);
+        });
+
+        SearchHandle {
+            receiver,
+            total_stores,
+        }
+    }
+}
+
+/// Consulta todas las tiendas en paralelo y envía sus eventos; los productos quedan
+/// marcados con la consulta que los encontró
+async fn search_stores(
+    query: &str,
+    stores: &[StoreConfig],
+    scraper: Arc<WebScraper>,
+    sender: &Sender<SearchEvent>,
+    notify: &Notify,
+) {
+    let mut tasks = JoinSet::new();
+
+    for store in stores.iter().cloned() {
+        let query = query.to_string();
+        let scraper = Arc::clone(&scraper);
+        let sender = sender.clone();
+        let notify = Arc::clone(notify);
+
+        // El scraper es bloqueante, así que cada tienda corre en el pool de bloqueo
+        tasks.spawn_blocking(move || {
+            send_event(&sender, &*notify, SearchEvent::StoreStarted {
+                store_name: store.name.clone(),
+            });
+            let result = scraper.search_products(&query, &store).map(|mut products| {
+                for product in &mut products {
+                    product.query = Some(query.clone());
+                }
+                products
+            });
+            (store.name, result)
+        });
+    }
+
+    // El último Arc del scraper no debe liberarse dentro del contexto async:
+    // el cliente bloqueante de reqwest entra en pánico si se destruye ahí
+    drop(scraper);
+
+    while let Some(joined) = tasks.join_next().await {
+        let event = match joined {
+            Ok((store_name, result)) => SearchEvent::StoreFinished { store_name, result },
+            Err(e) => SearchEvent::StoreFinished {
+                store_name: String::from("?"),
+                result: Err(ScrapeError::Internal {
+                    message: format!("La tarea de búsqueda falló: {}", e),
+                }),
+            },
+        };
+        send_event(sender, &**notify, event);
+    }
+}
+
+/// Devuelve false si el receptor ya no existe, es decir, si la búsqueda fue descartada
+fn send_event(sender: &Sender<SearchEvent>, notify: &(dyn Fn() + Send + Sync), event: SearchEvent) -> bool {
+    let sent = sender.send(event).is_ok();
+    if sent {
+        notify();
+    }
+    sent
+}
+
+/// Handle de una búsqueda en curso
+pub struct SearchHandle {
+    receiver: Receiver<SearchEvent>,
+    total_stores: usize,
+}
+
+impl SearchHandle {
+    /// Devuelve los eventos pendientes sin bloquear
+    pub fn poll(&self) -> Vec<SearchEvent> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// Espera el siguiente evento; `None` cuando la búsqueda ya no enviará más
+    pub fn wait_event(&self) -> Option<SearchEvent> {
+        self.receiver.recv().ok()
+    }
+
+    pub fn total_stores(&self) -> usize {
+        self.total_stores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FieldSpec;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    /// Servidor HTTP mínimo que devuelve un producto cuyo nombre es la consulta
+    fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buffer = [0u8; 4096];
+                let read = stream.read(&mut buffer).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..read]);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let query = path.rsplit("q=").next().unwrap_or_default();
+                let body = format!(
+                    r#"<div class="item"><a href="/p/{0}">{0}</a><span class="price">10</span></div>"#,
+                    query
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        address
+    }
+
+    fn store(name: &str, base_url: &str) -> StoreConfig {
+        let mut store = StoreConfig::new(name.to_string(), base_url.to_string());
+        store.search_url_pattern = "{base_url}/buscar?q={query}".to_string();
+        store.product_container_selector = ".item".to_string();
+        store.name_selector = FieldSpec::text("a");
+        store.price_selector = FieldSpec::text(".price");
+        store.link_selector = FieldSpec::attribute("a", "href");
+        store
+    }
+
+    fn engine() -> SearchEngine {
+        SearchEngine::new(Arc::new(WebScraper::for_tests(0)))
+    }
+
+    /// Lee eventos hasta `Finished`, que debe ser el último
+    fn collect(handle: &SearchHandle) -> Vec<SearchEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = handle.wait_event() {
+            let finished = matches!(event, SearchEvent::Finished);
+            events.push(event);
+            if finished {
+                break;
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn every_store_reports_its_result() {
+        let address = serve();
+        let stores = vec![store("Tienda", &address), store("Rota", "no es una url")];
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&notified);
+
+        let engine = engine();
+        let handle = engine.start_search("taladro", stores, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(handle.total_stores(), 2);
+
+        let events = collect(&handle);
+        assert!(matches!(events.last(), Some(SearchEvent::Finished)));
+        // Cuando el canal se cierra ya se avisó de todos los eventos
+        assert!(handle.wait_event().is_none());
+        assert_eq!(notified.load(Ordering::SeqCst), events.len());
+
+        let started = events.iter().filter(|e| matches!(e, SearchEvent::StoreStarted { .. })).count();
+        assert_eq!(started, 2);
+
+        for event in &events {
+            if let SearchEvent::StoreFinished { store_name, result } = event {
+                match store_name.as_str() {
+                    "Tienda" => {
+                        let products = result.as_ref().unwrap();
+                        assert_eq!(products.len(), 1);
+                        assert_eq!(products[0].name, "taladro");
+                        assert_eq!(products[0].query.as_deref(), Some("taladro"));
+                    }
+                    "Rota" => assert!(result.is_err()),
+                    other => panic!("tienda inesperada: {}", other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn search_without_stores_just_finishes() {
+        let engine = engine();
+        let handle = engine.start_search("taladro", Vec::new(), || {});
+        assert_eq!(handle.total_stores(), 0);
+        assert!(matches!(handle.wait_event(), Some(SearchEvent::Finished)));
+        assert!(handle.wait_event().is_none());
+    }
+
+    #[test]
+    fn batch_runs_queries_in_order() {
+        let address = serve();
+        let queries = vec!["taladro".to_string(), "sierra".to_string()];
+
+        let engine = engine();
+        let handle = engine.start_batch(queries, vec![store("Tienda", &address)], Duration::ZERO, || {});
+
+        let mut order = Vec::new();
+        for event in collect(&handle) {
+            match event {
+                SearchEvent::QueryStarted { index, query } => order.push(format!("inicio {} {}", index, query)),
+                SearchEvent::StoreFinished { result, .. } => {
+                    let products = result.unwrap();
+                    order.push(format!("productos {}", products[0].query.as_deref().unwrap_or_default()));
+                }
+                SearchEvent::QueryFinished { index } => order.push(format!("fin {}", index)),
+                SearchEvent::StoreStarted { .. } => {}
+                SearchEvent::Finished => order.push("terminado".to_string()),
+            }
+        }
+
+        assert_eq!(
+            order,
+            [
+                "inicio 0 taladro",
+                "productos taladro",
+                "fin 0",
+                "inicio 1 sierra",
+                "productos sierra",
+                "fin 1",
+                "terminado",
+            ]
+        );
+    }
+}
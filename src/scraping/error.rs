@@ -0,0 +1,133 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Errores que puede producir el scraping de una tienda
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScrapeError {
+    /// La petición superó el timeout de conexión o lectura
+    Timeout { url: String },
+    /// Fallo de red: DNS, conexión rechazada, TLS...
+    Network { url: String, message: String },
+    /// El servidor respondió con un código de error
+    HttpStatus {
+        url: String,
+        status: u16,
+        retry_after: Option<Duration>,
+    },
+    /// No se pudo leer o decodificar el cuerpo de la respuesta
+    Decode { url: String, message: String },
+    /// Un selector CSS de la tienda no es válido
+    Selector {
+        field: String,
+        selector: String,
+        message: String,
+    },
+    /// robots.txt no permite acceder a la URL
+    Robots { url: String },
+    /// La página es un captcha o un bloqueo anti-bots
+    Blocked { url: String, reason: String },
+    /// Modo sin conexión y la URL no está en la caché
+    Offline { url: String },
+    /// No se pudo construir el cliente HTTP
+    Client { message: String },
+    /// Fallo inesperado de la tarea de scraping
+    Internal { message: String },
+}
+
+impl ScrapeError {
+    pub fn from_reqwest(url: &str, error: reqwest::Error) -> Self {
+        // Cabecera o URL inválida, bucle de redirecciones: reintentar no cambia nada
+        let invalid_request =
+            error.is_request() && !error.is_connect() && error.url().is_none_or(|u| u.host_str().is_none());
+        if error.is_builder() || error.is_redirect() || invalid_request {
+            ScrapeError::Client {
+                message: format!("{}: {}", url, error),
+            }
+        } else if error.is_timeout() {
+            ScrapeError::Timeout { url: url.to_string() }
+        } else if error.is_decode() || error.is_body() {
+            ScrapeError::Decode {
+                url: url.to_string(),
+                message: error.to_string(),
+            }
+        } else {
+            ScrapeError::Network {
+                url: url.to_string(),
+                message: error.to_string(),
+            }
+        }
+    }
+
+    /// Indica si vale la pena reintentar la petición
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ScrapeError::Timeout { .. } | ScrapeError::Network { .. } => true,
+            ScrapeError::HttpStatus { status, .. } => *status == 408 || *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Espera pedida por el servidor mediante Retry-After
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ScrapeError::HttpStatus { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrapeError::Timeout { url } => write!(f, "Tiempo de espera agotado: {}", url),
+            ScrapeError::Network { url, message } => write!(f, "Error de conexión con {}: {}", url, message),
+            ScrapeError::HttpStatus { url, status, .. } => write!(f, "Error HTTP {} en {}", status, url),
+            ScrapeError::Decode { url, message } => write!(f, "Error al leer el contenido de {}: {}", url, message),
+            ScrapeError::Selector { field, selector, message } => {
+                write!(f, "Selector de {} inválido ({}): {}", field, selector, message)
+            }
+            ScrapeError::Robots { url } => write!(f, "robots.txt no permite acceder a {}", url),
+            ScrapeError::Blocked { url, reason } => write!(f, "Página bloqueada en {}: {}", url, reason),
+            ScrapeError::Offline { url } => write!(f, "Modo sin conexión: {} no está en la caché", url),
+            ScrapeError::Client { message } => write!(f, "Error al crear el cliente HTTP: {}", message),
+            ScrapeError::Internal { message } => write!(f, "Error interno: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ScrapeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_url_is_not_transient() {
+        let error = reqwest::blocking::Client::new().get("no es una url").send().unwrap_err();
+        let error = ScrapeError::from_reqwest("no es una url", error);
+        assert!(matches!(error, ScrapeError::Client { .. }));
+        assert!(!error.is_transient());
+    }
+
+    #[test]
+    fn invalid_header_is_not_transient() {
+        let error = reqwest::blocking::Client::new()
+            .get("http://127.0.0.1:9/")
+            .header("cabecera inválida", "x")
+            .send()
+            .unwrap_err();
+        assert!(!ScrapeError::from_reqwest("http://127.0.0.1:9/", error).is_transient());
+    }
+
+    #[test]
+    fn server_errors_and_rate_limits_are_transient() {
+        let status = |status| ScrapeError::HttpStatus {
+            url: String::new(),
+            status,
+            retry_after: None,
+        };
+        assert!(status(503).is_transient());
+        assert!(status(429).is_transient());
+        assert!(!status(404).is_transient());
+    }
+}
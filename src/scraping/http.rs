@@ -0,0 +1,743 @@
+use crate::models::HttpOverrides;
+use crate::scraping::cache::{CachedResponse, ResponseCache};
+use crate::scraping::cookies::CookieJars;
+use crate::scraping::error::ScrapeError;
+use crate::scraping::proxy::ProxyConfig;
+use crate::scraping::rate_limit::{RateLimitConfig, RateLimiter};
+use crate::scraping::retry::RetryPolicy;
+use crate::utils::AppConfig;
+use reqwest::blocking::Client;
+use reqwest::header;
+use reqwest::redirect::Policy;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Ajustes HTTP efectivos para una petición
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpSettings {
+    pub user_agent: String,
+    pub request_delay: Duration,
+    pub connect_timeout: Duration,
+    pub read_timeout: Duration,
+    pub max_redirects: usize,
+    pub retry: RetryPolicy,
+    pub rate_limit: RateLimitConfig,
+    pub proxy: ProxyConfig,
+    // Cabeceras y cookies iniciales propias de la tienda
+    pub headers: BTreeMap<String, String>,
+    pub cookies: BTreeMap<String, String>,
+    // Tienda cuyo tarro de cookies se usa; sin tarro no se guardan cookies
+    pub cookie_jar: Option<String>,
+    // Si las páginas pasan por la caché de respuestas; la prueba de selectores la activa
+    // aunque esté desactivada en config.json
+    pub use_cache: bool,
+}
+
+impl HttpSettings {
+    pub fn from_app_config(config: &AppConfig) -> Self {
+        Self {
+            user_agent: config.user_agent.clone(),
+            request_delay: Duration::from_millis(config.request_delay_ms),
+            connect_timeout: Duration::from_secs(config.connect_timeout_secs),
+            read_timeout: Duration::from_secs(config.read_timeout_secs),
+            max_redirects: config.max_redirects,
+            retry: config.retry.clone(),
+            rate_limit: config.rate_limit.clone(),
+            proxy: config.proxy.clone(),
+            headers: BTreeMap::new(),
+            cookies: BTreeMap::new(),
+            cookie_jar: None,
+            use_cache: config.cache.enabled || config.cache.offline,
+        }
+    }
+
+    /// Aplica los ajustes propios de una tienda sobre los globales
+    pub fn with_overrides(&self, overrides: &HttpOverrides) -> Self {
+        Self {
+            user_agent: overrides
+                .user_agent
+                .clone()
+                .filter(|ua| !ua.is_empty())
+                .unwrap_or_else(|| self.user_agent.clone()),
+            request_delay: overrides
+                .request_delay_ms
+                .map(Duration::from_millis)
+                .unwrap_or(self.request_delay),
+            connect_timeout: overrides
+                .connect_timeout_secs
+                .map(Duration::from_secs)
+                .unwrap_or(self.connect_timeout),
+            read_timeout: overrides
+                .read_timeout_secs
+                .map(Duration::from_secs)
+                .unwrap_or(self.read_timeout),
+            max_redirects: overrides.max_redirects.unwrap_or(self.max_redirects),
+            retry: overrides.retry.clone().unwrap_or_else(|| self.retry.clone()),
+            rate_limit: overrides
+                .rate_limit
+                .clone()
+                .unwrap_or_else(|| self.rate_limit.clone()),
+            proxy: overrides.proxy.clone().unwrap_or_else(|| self.proxy.clone()),
+            headers: overrides.headers.clone(),
+            cookies: overrides.cookies.clone(),
+            cookie_jar: self.cookie_jar.clone(),
+            use_cache: self.use_cache,
+        }
+    }
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self::from_app_config(&AppConfig::default())
+    }
+}
+
+/// Respuesta HTTP ya leída
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub retry_after: Option<Duration>,
+}
+
+impl HttpResponse {
+    /// Convierte los códigos que no son 2xx en `ScrapeError::HttpStatus`
+    pub fn into_success(self, url: &str) -> Result<Self, ScrapeError> {
+        if (200..300).contains(&self.status) {
+            Ok(self)
+        } else {
+            Err(ScrapeError::HttpStatus {
+                url: url.to_string(),
+                status: self.status,
+                retry_after: self.retry_after,
+            })
+        }
+    }
+}
+
+/// Interpreta Retry-After, que puede venir en segundos o como fecha HTTP
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Parte de los ajustes que sólo se puede fijar al construir el cliente
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    max_redirects: usize,
+    proxy: ProxyConfig,
+    // Cada tarro de cookies necesita su propio cliente
+    cookie_jar: Option<String>,
+}
+
+impl ClientKey {
+    fn from_settings(settings: &HttpSettings) -> Self {
+        Self {
+            connect_timeout: settings.connect_timeout,
+            read_timeout: settings.read_timeout,
+            max_redirects: settings.max_redirects,
+            proxy: settings.proxy.clone(),
+            cookie_jar: settings.cookie_jar.clone(),
+        }
+    }
+}
+
+/// Cliente HTTP compartido: reutiliza conexiones y respeta el delay entre peticiones y
+/// el límite de velocidad de cada dominio
+pub struct HttpClient {
+    defaults: HttpSettings,
+    clients: Mutex<HashMap<ClientKey, Client>>,
+    next_slot: Mutex<HashMap<String, Instant>>,
+    limiter: RateLimiter,
+    cache: Option<ResponseCache>,
+    cookies: Option<CookieJars>,
+}
+
+impl HttpClient {
+    pub fn new(defaults: HttpSettings) -> Self {
+        Self {
+            defaults,
+            clients: Mutex::new(HashMap::new()),
+            next_slot: Mutex::new(HashMap::new()),
+            limiter: RateLimiter::new(),
+            cache: None,
+            cookies: None,
+        }
+    }
+
+    /// Activa la caché en disco para `get_text`
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_ref()
+    }
+
+    /// Activa los tarros de cookies persistentes para las peticiones con `cookie_jar`
+    pub fn with_cookies(mut self, cookies: CookieJars) -> Self {
+        self.cookies = Some(cookies);
+        self
+    }
+
+    pub fn cookies(&self) -> Option<&CookieJars> {
+        self.cookies.as_ref()
+    }
+
+    pub fn defaults(&self) -> &HttpSettings {
+        &self.defaults
+    }
+
+    /// Ajustes efectivos para una tienda
+    pub fn settings_for(&self, overrides: &HttpOverrides) -> HttpSettings {
+        self.defaults.with_overrides(overrides)
+    }
+
+    /// Descarga una URL como texto usando los ajustes indicados, reintentando errores transitorios.
+    /// Si hay caché, sirve las entradas vigentes y revalida las vencidas con ETag/Last-Modified.
+    pub fn get_text(&self, url: &str, settings: &HttpSettings) -> Result<String, ScrapeError> {
+        let Some(cache) = self.cache.as_ref().filter(|_| settings.use_cache) else {
+            return settings.retry.run(|| self.get(url, settings)?.into_success(url)).map(|r| r.body);
+        };
+
+        // Las cabeceras y cookies de la tienda cambian el contenido (idioma, región), también
+        // las que el sitio dejó en el tarro
+        let jar_cookies = match self.cookies.as_ref().zip(settings.cookie_jar.as_deref()) {
+            Some((cookies, store_name)) => cookies.request_cookies(store_name, url),
+            None => Vec::new(),
+        };
+        let mut vary: Vec<(&str, &str)> = vec![("user-agent", &settings.user_agent)];
+        vary.extend(settings.headers.iter().map(|(name, value)| (name.as_str(), value.as_str())));
+        vary.extend(settings.cookies.iter().map(|(name, value)| (name.as_str(), value.as_str())));
+        vary.extend(jar_cookies.iter().map(|(name, value)| (name.as_str(), value.as_str())));
+        let key = ResponseCache::key(url, &vary);
+        let cached = cache.get(&key, url);
+
+        if let Some(entry) = &cached
+            && (cache.is_offline() || cache.is_fresh(entry))
+        {
+            return Ok(entry.body.clone());
+        }
+
+        if cache.is_offline() {
+            return Err(ScrapeError::Offline { url: url.to_string() });
+        }
+
+        let mut conditional = Vec::new();
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                conditional.push((header::IF_NONE_MATCH, etag.clone()));
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                conditional.push((header::IF_MODIFIED_SINCE, last_modified.clone()));
+            }
+        }
+
+        let response = settings.retry.run(|| {
+            let response = self.send(url, settings, &conditional)?;
+            if response.status == 304 && cached.is_some() {
+                Ok(response)
+            } else {
+                response.into_success(url)
+            }
+        })?;
+
+        if response.status == 304
+            && let Some(entry) = cached
+        {
+            let entry = cache.touch(&key, entry.clone()).unwrap_or(entry);
+            return Ok(entry.body);
+        }
+
+        let entry = CachedResponse::new(url, response.etag, response.last_modified, response.body);
+        if let Err(e) = cache.store(&key, &entry) {
+            eprintln!("{}", e);
+        }
+        Ok(entry.body)
+    }
+
+    /// Hace un GET y devuelve la respuesta sin importar el código de estado
+    pub fn get(&self, url: &str, settings: &HttpSettings) -> Result<HttpResponse, ScrapeError> {
+        if self.cache.as_ref().is_some_and(|c| c.is_offline()) {
+            return Err(ScrapeError::Offline { url: url.to_string() });
+        }
+        self.send(url, settings, &[])
+    }
+
+    /// Descarga una URL como bytes (imágenes). No usa la caché de respuestas ni el delay
+    /// entre peticiones, porque las imágenes suelen venir de una CDN y no de la tienda,
+    /// pero sí el límite de velocidad del dominio.
+    pub fn get_bytes(&self, url: &str, settings: &HttpSettings, max_bytes: usize) -> Result<Vec<u8>, ScrapeError> {
+        if self.cache.as_ref().is_some_and(|c| c.is_offline()) {
+            return Err(ScrapeError::Offline { url: url.to_string() });
+        }
+
+        settings.retry.run(|| {
+            let client = self.client_for(settings)?;
+            let _permit = self.limiter.acquire(url, &settings.rate_limit);
+            let response = client
+                .get(url)
+                .header(header::USER_AGENT, &settings.user_agent)
+                .send()
+                .map_err(|e| ScrapeError::from_reqwest(url, e))?;
+
+            let status = response.status().as_u16();
+            if !(200..300).contains(&status) {
+                let retry_after = response
+                    .headers()
+                    .get(header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_retry_after);
+                return Err(ScrapeError::HttpStatus {
+                    url: url.to_string(),
+                    status,
+                    retry_after,
+                });
+            }
+
+            // Se rechaza antes de leer si el servidor ya anuncia un cuerpo demasiado grande
+            let too_large = || ScrapeError::Decode {
+                url: url.to_string(),
+                message: format!("el contenido ocupa más de {} bytes", max_bytes),
+            };
+            if response.content_length().is_some_and(|length| length > max_bytes as u64) {
+                return Err(too_large());
+            }
+
+            // Sin Content-Length (o si miente) se corta al pasar del límite
+            let mut bytes = Vec::new();
+            response
+                .take(max_bytes as u64 + 1)
+                .read_to_end(&mut bytes)
+                .map_err(|e| body_error(url, e))?;
+            if bytes.len() > max_bytes {
+                return Err(too_large());
+            }
+            Ok(bytes)
+        })
+    }
+
+    fn send(
+        &self,
+        url: &str,
+        settings: &HttpSettings,
+        extra_headers: &[(header::HeaderName, String)],
+    ) -> Result<HttpResponse, ScrapeError> {
+        let client = self.client_for(settings)?;
+        let jar = self.cookies.as_ref().zip(settings.cookie_jar.as_deref());
+        if let Some((cookies, store_name)) = jar {
+            cookies.seed(store_name, url, &settings.cookies);
+        }
+
+        // Primero el delay entre peticiones: si se tomara antes el permiso, el hueco de
+        // `max_in_flight` quedaría ocupado mientras se duerme. El permiso se mantiene
+        // hasta leer el cuerpo, que es cuando termina la petición.
+        self.wait_turn(url, settings.request_delay);
+        let _permit = self.limiter.acquire(url, &settings.rate_limit);
+
+        let mut request = client.get(url).header(header::USER_AGENT, &settings.user_agent);
+        for (name, value) in &settings.headers {
+            request = request.header(name.trim(), value);
+        }
+        for (name, value) in extra_headers {
+            request = request.header(name, value);
+        }
+
+        let mut response = request.send().map_err(|e| ScrapeError::from_reqwest(url, e))?;
+        let status = response.status().as_u16();
+        let header_value = |name: header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let etag = header_value(header::ETAG);
+        let last_modified = header_value(header::LAST_MODIFIED);
+        let retry_after = header_value(header::RETRY_AFTER).and_then(|v| parse_retry_after(&v));
+
+        let content_type = header_value(header::CONTENT_TYPE);
+
+        // `text()` pondría un límite al cuerpo entero; leyendo por partes, el timeout
+        // del cliente se aplica a cada lectura y una descarga lenta que avanza no se corta
+        let mut bytes = Vec::new();
+        response.read_to_end(&mut bytes).map_err(|e| body_error(url, e))?;
+        let body = decode_body(&bytes, content_type.as_deref());
+
+        if let Some((cookies, store_name)) = jar
+            && let Err(e) = cookies.save(store_name)
+        {
+            eprintln!("{}", e);
+        }
+
+        Ok(HttpResponse {
+            status,
+            body,
+            etag,
+            last_modified,
+            retry_after,
+        })
+    }
+
+    /// Devuelve el cliente del pool para estos ajustes, creándolo si hace falta
+    fn client_for(&self, settings: &HttpSettings) -> Result<Client, ScrapeError> {
+        let key = ClientKey::from_settings(settings);
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+
+        let mut builder = Client::builder()
+            .user_agent(settings.user_agent.clone())
+            .connect_timeout(settings.connect_timeout)
+            // En el cliente bloqueante, límite para recibir las cabeceras y para cada
+            // lectura del cuerpo, no para la descarga completa
+            .timeout(settings.read_timeout)
+            .redirect(Policy::limited(settings.max_redirects));
+        if let Some((cookies, store_name)) = self.cookies.as_ref().zip(settings.cookie_jar.as_deref()) {
+            builder = builder.cookie_provider(cookies.jar(store_name));
+        }
+        let client = settings
+            .proxy
+            .apply(builder)?
+            .build()
+            .map_err(|e| ScrapeError::Client { message: e.to_string() })?;
+
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Espera lo necesario para respetar el delay entre peticiones al mismo host
+    fn wait_turn(&self, url: &str, delay: Duration) {
+        if delay.is_zero() {
+            return;
+        }
+
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_default();
+
+        // Se reserva el turno con el lock tomado y se duerme fuera de él
+        let wait = {
+            let mut slots = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let slot = slots.get(&host).copied().filter(|s| *s > now).unwrap_or(now);
+            slots.insert(host, slot + delay);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(HttpSettings::default())
+    }
+}
+
+/// Los errores al leer el cuerpo llegan como `io::Error` con el de reqwest dentro
+fn body_error(url: &str, error: std::io::Error) -> ScrapeError {
+    let message = error.to_string();
+    match error.into_inner().and_then(|inner| inner.downcast::<reqwest::Error>().ok()) {
+        Some(error) => ScrapeError::from_reqwest(url, *error),
+        None => ScrapeError::Network {
+            url: url.to_string(),
+            message,
+        },
+    }
+}
+
+/// Decodifica el cuerpo con el charset de Content-Type, o como UTF-8 si no lo indica
+fn decode_body(bytes: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(|value| {
+            value.split(';').skip(1).find_map(|param| {
+                let (name, label) = param.split_once('=')?;
+                name.trim().eq_ignore_ascii_case("charset").then(|| label.trim().trim_matches('"'))
+            })
+        })
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraping::CacheConfig;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Servidor de una sola respuesta en 127.0.0.1; devuelve su URL
+    fn serve_once(response: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request);
+                let _ = stream.write_all(&response);
+            }
+        });
+        format!("http://{}/imagen.png", address)
+    }
+
+    /// Servidor en 127.0.0.1 que da las respuestas en orden, una por conexión, y anota
+    /// cada petición completa
+    fn serve_sequence(responses: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        thread::spawn(move || {
+            for response in responses {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut request = [0u8; 4096];
+                let read = stream.read(&mut request).unwrap_or(0);
+                seen.lock().unwrap().push(String::from_utf8_lossy(&request[..read]).to_lowercase());
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (format!("http://{}/buscar?q=taladro", address), requests)
+    }
+
+    fn settings() -> HttpSettings {
+        let mut settings = HttpSettings::default();
+        settings.proxy.mode = crate::scraping::proxy::ProxyMode::Direct;
+        settings.request_delay = Duration::ZERO;
+        settings
+    }
+
+    fn cached_client(name: &str, config: CacheConfig) -> HttpClient {
+        let dir = std::env::temp_dir().join(format!("yam-webs-http-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        HttpClient::new(settings()).with_cache(ResponseCache::new(dir, &config))
+    }
+
+    fn cache_settings() -> HttpSettings {
+        HttpSettings {
+            use_cache: true,
+            ..settings()
+        }
+    }
+
+    #[test]
+    fn fresh_entry_is_served_without_a_request() {
+        let (url, requests) = serve_sequence(vec!["HTTP/1.1 200 OK\r\nContent-Length: 8\r\n\r\ntaladros"]);
+        let client = cached_client("fresh", CacheConfig::default());
+
+        assert_eq!(client.get_text(&url, &cache_settings()).unwrap(), "taladros");
+        assert_eq!(client.get_text(&url, &cache_settings()).unwrap(), "taladros");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn stale_entry_is_revalidated_with_its_etag() {
+        let (url, requests) = serve_sequence(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 8\r\n\r\ntaladros",
+            "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\n\r\n",
+        ]);
+        let config = CacheConfig {
+            ttl_secs: 0,
+            ..CacheConfig::default()
+        };
+        let client = cached_client("revalidate", config);
+
+        assert_eq!(client.get_text(&url, &cache_settings()).unwrap(), "taladros");
+        assert_eq!(client.get_text(&url, &cache_settings()).unwrap(), "taladros");
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+    }
+
+    #[test]
+    fn cache_is_skipped_unless_the_settings_ask_for_it() {
+        let (url, requests) = serve_sequence(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\nuno",
+            "HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\ndos",
+        ]);
+        let client = cached_client("skipped", CacheConfig::default());
+
+        assert_eq!(client.get_text(&url, &settings()).unwrap(), "uno");
+        assert_eq!(client.get_text(&url, &settings()).unwrap(), "dos");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn offline_mode_only_serves_the_cache() {
+        let config = CacheConfig {
+            offline: true,
+            ..CacheConfig::default()
+        };
+        let client = cached_client("offline", config);
+        let error = client.get_text("http://127.0.0.1:9/buscar", &cache_settings()).unwrap_err();
+        assert!(matches!(error, ScrapeError::Offline { .. }));
+    }
+
+    #[test]
+    fn announced_length_over_limit_is_rejected() {
+        let url = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 1000000\r\n\r\n".to_vec());
+        let error = HttpClient::default().get_bytes(&url, &settings(), 16).unwrap_err();
+        assert!(matches!(error, ScrapeError::Decode { .. }));
+    }
+
+    #[test]
+    fn body_without_length_is_cut_at_limit() {
+        let mut response = b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n".to_vec();
+        response.extend(std::iter::repeat_n(b'x', 64));
+        let url = serve_once(response);
+        let error = HttpClient::default().get_bytes(&url, &settings(), 16).unwrap_err();
+        assert!(matches!(error, ScrapeError::Decode { .. }));
+    }
+
+    #[test]
+    fn body_within_limit_is_returned() {
+        let url = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nPNG!".to_vec());
+        let bytes = HttpClient::default().get_bytes(&url, &settings(), 16).unwrap();
+        assert_eq!(bytes, b"PNG!");
+    }
+
+    #[test]
+    fn size_error_reports_the_limit_in_bytes() {
+        let url = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 1000000\r\n\r\n".to_vec());
+        match HttpClient::default().get_bytes(&url, &settings(), 500) {
+            Err(ScrapeError::Decode { message, .. }) => assert!(message.contains("500 bytes"), "{}", message),
+            other => panic!("se esperaba un error de tamaño: {:?}", other),
+        }
+    }
+
+    /// Servidor que envía las cabeceras y luego el cuerpo en trozos, con una pausa antes de cada uno
+    fn serve_slowly(chunks: Vec<&'static str>, pause: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request);
+                let length: usize = chunks.iter().map(|chunk| chunk.len()).sum();
+                let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", length);
+                let _ = stream.write_all(head.as_bytes());
+                for chunk in chunks {
+                    thread::sleep(pause);
+                    let _ = stream.write_all(chunk.as_bytes());
+                    let _ = stream.flush();
+                }
+            }
+        });
+        format!("http://{}/buscar", address)
+    }
+
+    fn read_timeout_settings() -> HttpSettings {
+        HttpSettings {
+            read_timeout: Duration::from_millis(300),
+            retry: RetryPolicy {
+                max_retries: 0,
+                ..RetryPolicy::default()
+            },
+            ..settings()
+        }
+    }
+
+    #[test]
+    fn slow_download_that_keeps_going_is_not_cut() {
+        // Cada trozo llega antes del timeout, pero la descarga entera tarda más
+        let url = serve_slowly(vec!["ta", "la", "dr", "os"], Duration::from_millis(150));
+        let body = HttpClient::default().get_text(&url, &read_timeout_settings()).unwrap();
+        assert_eq!(body, "taladros");
+    }
+
+    #[test]
+    fn stalled_download_times_out() {
+        let url = serve_slowly(vec!["taladros"], Duration::from_millis(1000));
+        let error = HttpClient::default().get_text(&url, &read_timeout_settings()).unwrap_err();
+        assert!(matches!(error, ScrapeError::Timeout { .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn body_is_decoded_with_the_declared_charset() {
+        assert_eq!(decode_body(b"Ca\xf1a", Some("text/html; charset=ISO-8859-1")), "Caña");
+        assert_eq!(decode_body("Caña".as_bytes(), Some("text/html")), "Caña");
+        assert_eq!(decode_body("Caña".as_bytes(), None), "Caña");
+    }
+
+    #[test]
+    fn store_overrides_win_over_global_settings() {
+        let global = settings();
+        let mut headers = BTreeMap::new();
+        headers.insert("Accept-Language".to_string(), "es-AR".to_string());
+        let proxy = ProxyConfig {
+            mode: crate::scraping::proxy::ProxyMode::Manual,
+            url: "http://proxy.example:8080".to_string(),
+            ..ProxyConfig::default()
+        };
+        let overrides = HttpOverrides {
+            user_agent: Some("YamBot/1.0".to_string()),
+            request_delay_ms: Some(2_500),
+            connect_timeout_secs: Some(3),
+            read_timeout_secs: Some(45),
+            max_redirects: Some(2),
+            retry: Some(RetryPolicy {
+                max_retries: 0,
+                ..RetryPolicy::default()
+            }),
+            rate_limit: Some(RateLimitConfig {
+                requests_per_second: 0.5,
+                burst: 1,
+                max_in_flight: 1,
+            }),
+            proxy: Some(proxy.clone()),
+            headers: headers.clone(),
+            cookies: BTreeMap::from([("region".to_string(), "ar".to_string())]),
+        };
+
+        let store = global.with_overrides(&overrides);
+        assert_eq!(store.user_agent, "YamBot/1.0");
+        assert_eq!(store.request_delay, Duration::from_millis(2_500));
+        assert_eq!(store.connect_timeout, Duration::from_secs(3));
+        assert_eq!(store.read_timeout, Duration::from_secs(45));
+        assert_eq!(store.max_redirects, 2);
+        assert_eq!(store.retry.max_retries, 0);
+        assert_eq!(store.rate_limit, overrides.rate_limit.unwrap());
+        assert_eq!(store.proxy, proxy);
+        assert_eq!(store.headers, headers);
+        assert_eq!(store.cookies.get("region").map(String::as_str), Some("ar"));
+    }
+
+    #[test]
+    fn unset_overrides_fall_back_to_global_settings() {
+        let mut global = settings();
+        global.cookie_jar = Some("Tienda".to_string());
+        global.use_cache = true;
+
+        assert_eq!(global.with_overrides(&HttpOverrides::default()), global);
+
+        // Un user agent vacío cuenta como no configurado
+        let empty_agent = HttpOverrides {
+            user_agent: Some(String::new()),
+            ..HttpOverrides::default()
+        };
+        assert_eq!(global.with_overrides(&empty_agent).user_agent, global.user_agent);
+    }
+}
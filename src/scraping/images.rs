@@ -0,0 +1,55 @@
+use crate::scraping::cache::{evict_oldest, ResponseCache};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Imágenes más grandes que esto no se descargan ni se guardan
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Caché en disco de las imágenes de productos, con límite de tamaño
+pub struct ImageCache {
+    dir: PathBuf,
+    max_size_bytes: u64,
+    // Serializa escrituras y desalojos entre hilos
+    write_lock: Mutex<()>,
+}
+
+impl ImageCache {
+    pub fn new(dir: impl Into<PathBuf>, max_size_mb: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size_bytes: max_size_mb.saturating_mul(1024 * 1024),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn get(&self, url: &str) -> Option<Vec<u8>> {
+        fs::read(self.entry_path(url)).ok()
+    }
+
+    pub fn store(&self, url: &str, bytes: &[u8]) -> Result<(), String> {
+        if self.max_size_bytes == 0 {
+            return Ok(());
+        }
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Error al crear la caché de imágenes: {}", e))?;
+        fs::write(self.entry_path(url), bytes).map_err(|e| format!("Error al guardar la imagen: {}", e))?;
+
+        evict_oldest(&self.dir, self.max_size_bytes);
+        Ok(())
+    }
+
+    /// Borra todas las imágenes guardadas
+    pub fn clear(&self) -> Result<(), String> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir).map_err(|e| format!("Error al vaciar la caché de imágenes: {}", e))?;
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.img", ResponseCache::key(url, &[])))
+    }
+}
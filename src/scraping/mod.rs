@@ -1,5 +1,31 @@
+pub mod cache;
+pub mod cookies;
+pub mod engine;
+pub mod error;
+pub mod http;
+pub mod images;
+pub mod proxy;
+pub mod rate_limit;
+pub mod retry;
+pub mod robots;
+pub mod scheduler;
 pub mod scraper;
+pub mod selector_test;
 pub mod selectors;
+pub mod structured;
 
+pub use cache::{CacheConfig, CachedResponse, ResponseCache};
+pub use cookies::{CookieJars, StoredCookie};
+pub use engine::{SearchEngine, SearchEvent, SearchHandle};
+pub use error::ScrapeError;
+pub use http::{HttpClient, HttpResponse, HttpSettings};
+pub use images::ImageCache;
+pub use proxy::{ProxyConfig, ProxyMode};
+pub use rate_limit::{RateLimitConfig, RateLimiter, RatePermit};
+pub use retry::RetryPolicy;
+pub use robots::{RobotsCache, RobotsPolicy, RobotsRules, RobotsVerdict};
+pub use scheduler::{CompletedRun, Scheduler};
 pub use scraper::WebScraper;
-pub use selectors::SelectorHelper;
\ No newline at end of file
+pub use selector_test::{SelectorCheck, SelectorTestReport};
+pub use selectors::SelectorHelper;
+pub use structured::StructuredDataExtractor;
@@ -0,0 +1,192 @@
+use crate::scraping::error::ScrapeError;
+use reqwest::{NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+
+/// Cómo salen las peticiones hacia las tiendas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProxyMode {
+    /// Lo que indiquen las variables HTTP_PROXY, HTTPS_PROXY y NO_PROXY del sistema
+    #[default]
+    System,
+    /// Conexión directa, ignorando también el proxy del sistema
+    Direct,
+    /// El proxy de `ProxyConfig::url`
+    Manual,
+}
+
+/// Proxy HTTP o SOCKS5, global en config.json o propio de una tienda
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    // http://, https://, socks5:// o socks5h:// (resuelve los nombres en el proxy)
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    // Hosts, dominios (".empresa.com" incluye los subdominios), IPs o redes que no
+    // pasan por el proxy
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.mode != ProxyMode::Manual {
+            return Ok(());
+        }
+
+        let url = reqwest::Url::parse(self.url.trim()).map_err(|e| format!("URL de proxy inválida: {}", e))?;
+        match url.scheme() {
+            "http" | "https" | "socks5" | "socks5h" => Ok(()),
+            scheme => Err(format!(
+                "Esquema de proxy no soportado \"{}\"; usa http, https, socks5 o socks5h",
+                scheme
+            )),
+        }
+    }
+
+    /// Descripción corta para la interfaz, sin credenciales
+    pub fn describe(&self) -> String {
+        match self.mode {
+            ProxyMode::System => "Proxy del sistema".to_string(),
+            ProxyMode::Direct => "Sin proxy".to_string(),
+            ProxyMode::Manual => match reqwest::Url::parse(self.url.trim()) {
+                Ok(url) => format!(
+                    "{}://{}{}",
+                    url.scheme(),
+                    url.host_str().unwrap_or_default(),
+                    url.port().map(|port| format!(":{}", port)).unwrap_or_default()
+                ),
+                Err(_) => self.url.clone(),
+            },
+        }
+    }
+
+    /// Aplica el proxy al constructor del cliente
+    pub(crate) fn apply(
+        &self,
+        builder: reqwest::blocking::ClientBuilder,
+    ) -> Result<reqwest::blocking::ClientBuilder, ScrapeError> {
+        match self.mode {
+            ProxyMode::System => Ok(builder),
+            ProxyMode::Direct => Ok(builder.no_proxy()),
+            ProxyMode::Manual => {
+                self.validate().map_err(|message| ScrapeError::Client { message })?;
+                let mut proxy = Proxy::all(self.url.trim()).map_err(|e| ScrapeError::Client {
+                    message: format!("Proxy inválido: {}", e),
+                })?;
+
+                if let Some(username) = self.username.as_deref().filter(|u| !u.is_empty()) {
+                    proxy = proxy.basic_auth(username, self.password.as_deref().unwrap_or_default());
+                }
+
+                let no_proxy: Vec<&str> = self
+                    .no_proxy
+                    .iter()
+                    .map(|entry| entry.trim())
+                    .filter(|entry| !entry.is_empty())
+                    .collect();
+                if !no_proxy.is_empty() {
+                    proxy = proxy.no_proxy(NoProxy::from_string(&no_proxy.join(",")));
+                }
+
+                Ok(builder.proxy(proxy))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraping::http::{HttpClient, HttpSettings};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Servidor HTTP mínimo en 127.0.0.1 que responde `label` y anota la línea de
+    /// petición de cada conexión
+    fn serve(label: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("127.0.0.1:{}", listener.local_addr().unwrap().port());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buffer = [0u8; 4096];
+                let read = stream.read(&mut buffer).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..read]);
+                seen.lock().unwrap().push(request.lines().next().unwrap_or_default().to_string());
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    label.len(),
+                    label
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (address, requests)
+    }
+
+    fn settings(proxy: ProxyConfig) -> HttpSettings {
+        HttpSettings {
+            proxy,
+            ..HttpSettings::default()
+        }
+    }
+
+    fn manual(address: &str, no_proxy: &[&str]) -> ProxyConfig {
+        ProxyConfig {
+            mode: ProxyMode::Manual,
+            url: format!("http://{}", address),
+            no_proxy: no_proxy.iter().map(|entry| entry.to_string()).collect(),
+            ..ProxyConfig::default()
+        }
+    }
+
+    #[test]
+    fn manual_proxy_receives_the_request() {
+        let (proxy, requests) = serve("proxy");
+        let client = HttpClient::default();
+
+        // El host no existe: sólo el proxy puede responder
+        let response = client.get("http://tienda.invalid/buscar?q=taladro", &settings(manual(&proxy, &[]))).unwrap();
+        assert_eq!(response.body, "proxy");
+        assert_eq!(
+            requests.lock().unwrap().as_slice(),
+            ["GET http://tienda.invalid/buscar?q=taladro HTTP/1.1"]
+        );
+    }
+
+    #[test]
+    fn no_proxy_and_direct_skip_the_proxy() {
+        let (proxy, proxy_requests) = serve("proxy");
+        let (origin, _) = serve("tienda");
+        let client = HttpClient::default();
+        let url = format!("http://{}/buscar", origin);
+
+        let bypassed = client.get(&url, &settings(manual(&proxy, &["127.0.0.1"]))).unwrap();
+        assert_eq!(bypassed.body, "tienda");
+
+        let direct = ProxyConfig {
+            mode: ProxyMode::Direct,
+            ..manual(&proxy, &[])
+        };
+        assert_eq!(client.get(&url, &settings(direct)).unwrap().body, "tienda");
+
+        assert!(proxy_requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn manual_proxy_requires_a_supported_url() {
+        assert!(manual("127.0.0.1:8080", &[]).validate().is_ok());
+        let ftp = ProxyConfig {
+            url: "ftp://proxy.example".to_string(),
+            ..manual("", &[])
+        };
+        assert!(ftp.validate().is_err());
+        assert!(ProxyConfig::default().validate().is_ok());
+    }
+}
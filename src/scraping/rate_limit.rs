@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Límite de peticiones por dominio: cubeta de tokens y máximo de peticiones simultáneas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    // Ritmo sostenido; 0 desactiva la cubeta
+    pub requests_per_second: f64,
+    // Peticiones que se pueden hacer seguidas antes de bajar al ritmo sostenido
+    pub burst: u32,
+    // Peticiones en curso a la vez contra el mismo dominio; 0 sin límite
+    pub max_in_flight: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 2.0,
+            burst: 4,
+            max_in_flight: 2,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    fn capacity(&self) -> f64 {
+        f64::from(self.burst.max(1))
+    }
+
+    fn has_bucket(&self) -> bool {
+        self.requests_per_second > 0.0
+    }
+}
+
+/// Estado de un dominio; los tokens se recargan de forma perezosa al consultarlo
+struct DomainState {
+    tokens: f64,
+    refilled_at: Instant,
+    in_flight: usize,
+}
+
+impl DomainState {
+    fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+        if config.has_bucket() {
+            let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * config.requests_per_second).min(config.capacity());
+        }
+        self.refilled_at = now;
+    }
+}
+
+/// Reparte los turnos por dominio entre todos los hilos que comparten el cliente HTTP
+pub struct RateLimiter {
+    domains: Mutex<HashMap<String, DomainState>>,
+    // Avisa cuando termina una petición y queda un hueco libre
+    released: Condvar,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self {
+            domains: Mutex::new(HashMap::new()),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Bloquea hasta que el dominio de la URL admite otra petición. El permiso ocupa un
+    /// hueco de `max_in_flight` hasta que se suelta.
+    pub fn acquire(&self, url: &str, config: &RateLimitConfig) -> RatePermit<'_> {
+        let domain = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+            .unwrap_or_default();
+
+        let mut domains = self.domains.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let now = Instant::now();
+            let state = domains.entry(domain.clone()).or_insert_with(|| DomainState {
+                tokens: config.capacity(),
+                refilled_at: now,
+                in_flight: 0,
+            });
+            state.refill(config, now);
+
+            let slot_free = config.max_in_flight == 0 || state.in_flight < config.max_in_flight;
+            let token_ready = !config.has_bucket() || state.tokens >= 1.0;
+
+            if slot_free && token_ready {
+                if config.has_bucket() {
+                    state.tokens -= 1.0;
+                }
+                state.in_flight += 1;
+                return RatePermit {
+                    limiter: self,
+                    domain,
+                };
+            }
+
+            // Sin hueco se espera a que otra petición termine; sin token, lo que falte
+            // para recargar uno
+            let wait = if token_ready {
+                None
+            } else {
+                Some(Duration::from_secs_f64((1.0 - state.tokens) / config.requests_per_second))
+            };
+            domains = match wait {
+                Some(wait) => self
+                    .released
+                    .wait_timeout(domains, wait)
+                    .map(|(guard, _)| guard)
+                    .unwrap_or_else(|e| e.into_inner().0),
+                None => self.released.wait(domains).unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+    }
+
+    fn release(&self, domain: &str) {
+        let mut domains = self.domains.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = domains.get_mut(domain) {
+            state.in_flight = state.in_flight.saturating_sub(1);
+        }
+        self.released.notify_all();
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turno concedido por `RateLimiter::acquire`; libera el hueco al destruirse
+pub struct RatePermit<'a> {
+    limiter: &'a RateLimiter,
+    domain: String,
+}
+
+impl Drop for RatePermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release(&self.domain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    const URL: &str = "https://tienda.example/buscar";
+
+    fn config(requests_per_second: f64, burst: u32, max_in_flight: usize) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_second,
+            burst,
+            max_in_flight,
+        }
+    }
+
+    #[test]
+    fn burst_is_immediate_then_sustained_rate_applies() {
+        let limiter = RateLimiter::new();
+        let config = config(20.0, 3, 0);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            drop(limiter.acquire(URL, &config));
+        }
+        assert!(start.elapsed() < Duration::from_millis(30));
+
+        // El cuarto token tarda 1/20 s en recargarse
+        drop(limiter.acquire(URL, &config));
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn domains_have_separate_buckets() {
+        let limiter = RateLimiter::new();
+        let config = config(1.0, 1, 0);
+        let start = Instant::now();
+
+        drop(limiter.acquire(URL, &config));
+        drop(limiter.acquire("https://otra.example/", &config));
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn in_flight_limit_waits_for_release() {
+        let limiter = Arc::new(RateLimiter::new());
+        let config = config(0.0, 1, 1);
+
+        let first = limiter.acquire(URL, &config);
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            let config = config.clone();
+            thread::spawn(move || {
+                let start = Instant::now();
+                drop(limiter.acquire(URL, &config));
+                start.elapsed()
+            })
+        };
+
+        thread::sleep(Duration::from_millis(80));
+        drop(first);
+        assert!(waiter.join().unwrap() >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn zero_rate_and_zero_in_flight_never_wait() {
+        let limiter = RateLimiter::new();
+        let config = config(0.0, 1, 0);
+        let start = Instant::now();
+
+        let permits: Vec<_> = (0..50).map(|_| limiter.acquire(URL, &config)).collect();
+        assert_eq!(permits.len(), 50);
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
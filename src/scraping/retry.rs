@@ -0,0 +1,163 @@
+use crate::scraping::error::ScrapeError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Política de reintentos para errores transitorios
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    // Reintentos además del primer intento
+    pub max_retries: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Espera antes del reintento `attempt` (empezando en 1): backoff exponencial con
+    /// jitter, o el Retry-After del servidor si lo envió
+    pub fn delay_for(&self, attempt: u32, error: &ScrapeError) -> Duration {
+        let max_delay = Duration::from_millis(self.max_delay_ms);
+
+        if let Some(retry_after) = error.retry_after() {
+            return retry_after.min(max_delay);
+        }
+
+        let exponential = self
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(20))
+            .min(self.max_delay_ms);
+        Duration::from_millis(rand::rng().random_range(exponential / 2..=exponential))
+    }
+
+    /// Ejecuta `operation` reintentando mientras el error sea transitorio
+    pub fn run<T, F>(&self, mut operation: F) -> Result<T, ScrapeError>
+    where
+        F: FnMut() -> Result<T, ScrapeError>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation() {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_transient() && attempt < self.max_retries => {
+                    attempt += 1;
+                    let delay = self.delay_for(attempt, &e);
+                    eprintln!(
+                        "{}; reintento {}/{} en {} ms",
+                        e,
+                        attempt,
+                        self.max_retries,
+                        delay.as_millis()
+                    );
+                    std::thread::sleep(delay);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay_ms: 1,
+            max_delay_ms: 2,
+        }
+    }
+
+    fn status(status: u16, retry_after: Option<Duration>) -> ScrapeError {
+        ScrapeError::HttpStatus {
+            url: "https://tienda.example".to_string(),
+            status,
+            retry_after,
+        }
+    }
+
+    #[test]
+    fn transient_errors_are_retried_up_to_the_limit() {
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = policy(2).run(|| {
+            attempts.set(attempts.get() + 1);
+            Err(status(503, None))
+        });
+        assert_eq!(result, Err(status(503, None)));
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[test]
+    fn success_after_a_retry_is_returned() {
+        let attempts = Cell::new(0);
+        let result = policy(3).run(|| {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < 2 {
+                Err(ScrapeError::Timeout {
+                    url: "https://tienda.example".to_string(),
+                })
+            } else {
+                Ok("página")
+            }
+        });
+        assert_eq!(result, Ok("página"));
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[test]
+    fn fatal_errors_are_not_retried() {
+        for error in [
+            status(404, None),
+            ScrapeError::Robots {
+                url: "https://tienda.example".to_string(),
+            },
+        ] {
+            let attempts = Cell::new(0);
+            let result: Result<(), _> = policy(3).run(|| {
+                attempts.set(attempts.get() + 1);
+                Err(error.clone())
+            });
+            assert_eq!(result, Err(error));
+            assert_eq!(attempts.get(), 1);
+        }
+    }
+
+    #[test]
+    fn retry_after_is_capped() {
+        let policy = RetryPolicy::default();
+        let asked = status(429, Some(Duration::from_secs(5)));
+        assert_eq!(policy.delay_for(1, &asked), Duration::from_secs(5));
+        let too_long = status(429, Some(Duration::from_secs(3600)));
+        assert_eq!(policy.delay_for(1, &too_long), Duration::from_millis(policy.max_delay_ms));
+    }
+
+    #[test]
+    fn backoff_doubles_with_jitter_and_stays_under_the_maximum() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay_ms: 100,
+            max_delay_ms: 1_000,
+        };
+        let error = status(503, None);
+        for _ in 0..50 {
+            let first = policy.delay_for(1, &error).as_millis();
+            assert!((50..=100).contains(&first), "{}", first);
+            let third = policy.delay_for(3, &error).as_millis();
+            assert!((200..=400).contains(&third), "{}", third);
+            let late = policy.delay_for(40, &error).as_millis();
+            assert!((500..=1_000).contains(&late), "{}", late);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::scraping::http::{HttpClient, HttpSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Qué hacer cuando robots.txt no permite una URL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RobotsPolicy {
+    /// Rechazar la petición
+    #[default]
+    Enforce,
+    /// Avisar por consola pero continuar
+    Warn,
+    /// No consultar robots.txt
+    Ignore,
+}
+
+/// Resultado de consultar robots.txt para una URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotsVerdict {
+    pub allowed: bool,
+    pub crawl_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RuleGroup {
+    user_agents: Vec<String>,
+    // (permitido, patrón)
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+/// Reglas de un robots.txt ya interpretado
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    groups: Vec<RuleGroup>,
+}
+
+impl RobotsRules {
+    /// Interpreta el contenido de un robots.txt
+    pub fn parse(content: &str) -> Self {
+        let mut groups: Vec<RuleGroup> = Vec::new();
+        let mut current = RuleGroup::default();
+        let mut last_was_agent = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    // Varias líneas User-agent seguidas comparten el mismo grupo
+                    if !last_was_agent && (!current.user_agents.is_empty() || !current.rules.is_empty()) {
+                        groups.push(std::mem::take(&mut current));
+                    }
+                    // Un User-agent vacío no nombra a nadie; "" coincidiría con todos
+                    if !value.is_empty() {
+                        current.user_agents.push(value.to_lowercase());
+                    }
+                    last_was_agent = true;
+                }
+                "allow" | "disallow" => {
+                    last_was_agent = false;
+                    // "Disallow:" vacío significa que todo está permitido
+                    if !value.is_empty() {
+                        current.rules.push((key == "allow", value.to_string()));
+                    }
+                }
+                "crawl-delay" => {
+                    last_was_agent = false;
+                    // Negativos, NaN o infinitos se ignoran
+                    current.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+                }
+                _ => {}
+            }
+        }
+
+        if !current.user_agents.is_empty() {
+            groups.push(current);
+        }
+
+        Self { groups }
+    }
+
+    /// Evalúa una ruta (con query) para el user agent indicado
+    pub fn check(&self, user_agent: &str, path: &str) -> RobotsVerdict {
+        let Some(group) = self.group_for(user_agent) else {
+            return RobotsVerdict {
+                allowed: true,
+                crawl_delay: None,
+            };
+        };
+
+        // Gana la regla más específica (patrón más largo); en empate gana Allow
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &group.rules {
+            if pattern_matches(pattern, path) {
+                let candidate = (pattern.len(), *allow);
+                if best.is_none_or(|b| candidate > b) {
+                    best = Some(candidate);
+                }
+            }
+        }
+
+        RobotsVerdict {
+            allowed: best.is_none_or(|(_, allow)| allow),
+            crawl_delay: group.crawl_delay,
+        }
+    }
+
+    /// Busca el grupo cuyo nombre coincide con el user agent; si no hay, el de "*"
+    fn group_for(&self, user_agent: &str) -> Option<&RuleGroup> {
+        let user_agent = user_agent.to_lowercase();
+
+        self.groups
+            .iter()
+            .filter_map(|group| {
+                group
+                    .user_agents
+                    .iter()
+                    .filter(|ua| ua.as_str() != "*" && user_agent.contains(ua.as_str()))
+                    .map(|ua| ua.len())
+                    .max()
+                    .map(|len| (len, group))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, group)| group)
+            .or_else(|| self.groups.iter().find(|g| g.user_agents.iter().any(|ua| ua == "*")))
+    }
+}
+
+/// Coincidencia de patrones de robots.txt con soporte para `*` y `$`
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern: Vec<char> = pattern.chars().collect();
+    // Sin `$` final el patrón es un prefijo, equivalente a terminar en `*`
+    if pattern.last() == Some(&'$') {
+        pattern.pop();
+    } else {
+        pattern.push('*');
+    }
+    let path: Vec<char> = path.chars().collect();
+
+    let (mut p, mut s) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while s < path.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, s));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == path[s] {
+            p += 1;
+            s += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            s = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+struct CachedRobots {
+    rules: Arc<RobotsRules>,
+    fetched_at: Instant,
+}
+
+/// Descarga y guarda en memoria el robots.txt de cada dominio
+pub struct RobotsCache {
+    entries: Mutex<HashMap<String, CachedRobots>>,
+    ttl: Duration,
+}
+
+impl RobotsCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+
+    /// Consulta si la URL puede pedirse, descargando robots.txt si no está en caché
+    pub fn check(&self, http: &HttpClient, url: &str, settings: &HttpSettings) -> RobotsVerdict {
+        let Ok(parsed) = reqwest::Url::parse(url) else {
+            return RobotsVerdict {
+                allowed: true,
+                crawl_delay: None,
+            };
+        };
+
+        let origin = parsed.origin().ascii_serialization();
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        let rules = self.rules_for(http, &origin, settings);
+        rules.check(&settings.user_agent, &path)
+    }
+
+    fn rules_for(&self, http: &HttpClient, origin: &str, settings: &HttpSettings) -> Arc<RobotsRules> {
+        {
+            let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(cached) = entries.get(origin)
+                && cached.fetched_at.elapsed() < self.ttl
+            {
+                return Arc::clone(&cached.rules);
+            }
+        }
+
+        let robots_url = format!("{}/robots.txt", origin);
+        let rules = match http.get(&robots_url, settings) {
+            Ok(response) if response.status == 200 => RobotsRules::parse(&response.body),
+            // 4xx significa que no hay restricciones
+            Ok(response) if (400..500).contains(&response.status) => RobotsRules::default(),
+            Ok(response) => {
+                eprintln!("robots.txt de {} respondió {}; se asume permitido", origin, response.status);
+                RobotsRules::default()
+            }
+            Err(e) => {
+                eprintln!("No se pudo obtener robots.txt de {}: {}", origin, e);
+                RobotsRules::default()
+            }
+        };
+
+        let rules = Arc::new(rules);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(
+            origin.to_string(),
+            CachedRobots {
+                rules: Arc::clone(&rules),
+                fetched_at: Instant::now(),
+            },
+        );
+        rules
+    }
+}
+
+impl Default for RobotsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+User-agent: *
+Disallow: /buscar
+Allow: /buscar/ofertas
+Disallow: /*.pdf$
+Crawl-delay: 2
+
+User-agent: YamBot
+User-agent: OtroBot
+Disallow: /privado # comentario
+Disallow:
+";
+
+    fn allowed(user_agent: &str, path: &str) -> bool {
+        RobotsRules::parse(ROBOTS).check(user_agent, path).allowed
+    }
+
+    #[test]
+    fn prefix_rules_apply() {
+        assert!(!allowed("Mozilla/5.0", "/buscar?q=tornillo"));
+        assert!(allowed("Mozilla/5.0", "/producto/1"));
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert!(allowed("Mozilla/5.0", "/buscar/ofertas?page=2"));
+    }
+
+    #[test]
+    fn wildcard_and_end_anchor() {
+        assert!(!allowed("Mozilla/5.0", "/docs/manual.pdf"));
+        assert!(allowed("Mozilla/5.0", "/docs/manual.pdf?v=2"));
+    }
+
+    #[test]
+    fn specific_group_replaces_wildcard_group() {
+        assert!(allowed("Mozilla/5.0 (compatible; YamBot/1.0)", "/buscar"));
+        assert!(!allowed("OtroBot", "/privado/datos"));
+        // Las líneas User-agent seguidas comparten grupo
+        assert!(allowed("otrobot", "/buscar"));
+    }
+
+    #[test]
+    fn crawl_delay_comes_from_matching_group() {
+        let rules = RobotsRules::parse(ROBOTS);
+        assert_eq!(rules.check("Mozilla/5.0", "/").crawl_delay, Some(Duration::from_secs(2)));
+        assert_eq!(rules.check("YamBot", "/").crawl_delay, None);
+    }
+
+    #[test]
+    fn invalid_crawl_delay_is_ignored() {
+        for value in ["-1", "inf", "NaN", "pronto"] {
+            let rules = RobotsRules::parse(&format!("User-agent: *\nCrawl-delay: {}\n", value));
+            assert_eq!(rules.check("Mozilla/5.0", "/").crawl_delay, None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn empty_user_agent_matches_nobody() {
+        let rules = RobotsRules::parse("User-agent:\nDisallow: /\n\nUser-agent: *\nDisallow: /privado\n");
+        assert!(rules.check("Mozilla/5.0", "/buscar").allowed);
+        assert!(!rules.check("Mozilla/5.0", "/privado").allowed);
+    }
+
+    #[test]
+    fn empty_robots_allows_everything() {
+        let verdict = RobotsRules::parse("").check("Mozilla/5.0", "/buscar");
+        assert!(verdict.allowed);
+        assert_eq!(verdict.crawl_delay, None);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::models::{Product, ScheduleRun, ScheduledSearch, StoreConfig, StoreManager};
+use crate::scraping::{SearchEngine, SearchEvent, SearchHandle, WebScraper};
+use crate::utils::FileManager;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Ejecución programada que acaba de terminar, para evaluar alertas o guardar historial
+#[derive(Debug)]
+pub struct CompletedRun {
+    pub schedule_name: String,
+    pub query: String,
+    pub products: Vec<Product>,
+    pub run: ScheduleRun,
+}
+
+struct RunningSearch {
+    query: String,
+    started_at: DateTime<Utc>,
+    handle: SearchHandle,
+    products: Vec<Product>,
+    errors: Vec<String>,
+}
+
+/// Lanza las búsquedas programadas cuando les toca. Lo usan tanto la interfaz como el
+/// modo daemon de la CLI; hay que llamar a `start_due` y `poll` periódicamente.
+pub struct Scheduler {
+    engine: SearchEngine,
+    file_manager: FileManager,
+    // Última ejecución de cada programación, persistida en schedule_state.json
+    last_runs: BTreeMap<String, ScheduleRun>,
+    running: HashMap<String, RunningSearch>,
+}
+
+impl Scheduler {
+    pub fn new(scraper: Arc<WebScraper>) -> Self {
+        let file_manager = FileManager::new();
+        let last_runs = file_manager.load_schedule_state();
+
+        Self {
+            engine: SearchEngine::new(scraper),
+            file_manager,
+            last_runs,
+            running: HashMap::new(),
+        }
+    }
+
+    pub fn last_run(&self, schedule_name: &str) -> Option<&ScheduleRun> {
+        self.last_runs.get(schedule_name)
+    }
+
+    pub fn is_running(&self, schedule_name: &str) -> bool {
+        self.running.contains_key(schedule_name)
+    }
+
+    /// Próxima ejecución de la programación, o el error de su expresión
+    pub fn next_run(&self, schedule: &ScheduledSearch, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+        let last_run = self.last_run(&schedule.name).map(|run| run.started_at);
+        schedule.next_run(last_run, now)
+    }
+
+    /// Momento más cercano en que hay que volver a mirar, para dormir hasta entonces
+    pub fn next_wakeup(&self, schedules: &[ScheduledSearch], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        schedules
+            .iter()
+            .filter(|schedule| schedule.enabled && !self.is_running(&schedule.name))
+            .filter_map(|schedule| self.next_run(schedule, now).ok())
+            .min()
+    }
+
+    /// Lanza las programaciones activas cuya hora ya llegó
+    pub fn start_due<F>(&mut self, schedules: &[ScheduledSearch], store_manager: &StoreManager, now: DateTime<Utc>, notify: F)
+    where
+        F: Fn() + Clone + Send + Sync + 'static,
+    {
+        for schedule in schedules {
+            if !schedule.enabled || self.is_running(&schedule.name) {
+                continue;
+            }
+            if self.next_run(schedule, now).is_ok_and(|next| next <= now) {
+                self.run_now(schedule, store_manager, notify.clone());
+            }
+        }
+    }
+
+    /// Ejecuta la programación ahora, le toque o no
+    pub fn run_now<F>(&mut self, schedule: &ScheduledSearch, store_manager: &StoreManager, notify: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        if self.is_running(&schedule.name) {
+            return;
+        }
+
+        let started_at = Utc::now();
+        let stores = select_stores(schedule, store_manager);
+        if stores.is_empty() {
+            // Se registra como ejecución fallida para que no se reintente en cada vuelta
+            let run = ScheduleRun {
+                started_at,
+                finished_at: started_at,
+                product_count: 0,
+                errors: vec!["Ninguna de las tiendas de la programación está disponible".to_string()],
+                snapshot: None,
+            };
+            self.finish(&schedule.name, run);
+            return;
+        }
+
+        let handle = self.engine.start_search(&schedule.query, stores, notify);
+        self.running.insert(
+            schedule.name.clone(),
+            RunningSearch {
+                query: schedule.query.clone(),
+                started_at,
+                handle,
+                products: Vec::new(),
+                errors: Vec::new(),
+            },
+        );
+    }
+
+    /// Procesa los eventos de las búsquedas en curso; las terminadas se guardan como
+    /// instantánea y se devuelven
+    pub fn poll(&mut self) -> Vec<CompletedRun> {
+        let mut finished = Vec::new();
+
+        for (name, search) in &mut self.running {
+            for event in search.handle.poll() {
+                match event {
+                    SearchEvent::StoreStarted { .. }
+                    | SearchEvent::QueryStarted { .. }
+                    | SearchEvent::QueryFinished { .. } => {}
+                    SearchEvent::StoreFinished { store_name, result } => match result {
+                        Ok(products) => search.products.extend(products),
+                        Err(e) => search.errors.push(format!("{}: {}", store_name, e)),
+                    },
+                    SearchEvent::Finished => finished.push(name.clone()),
+                }
+            }
+        }
+
+        finished
+            .into_iter()
+            .filter_map(|name| {
+                let mut search = self.running.remove(&name)?;
+                let finished_at = Utc::now();
+
+                let snapshot = match self.file_manager.save_snapshot(&name, &search.products, finished_at) {
+                    Ok(path) => Some(path),
+                    Err(e) => {
+                        search.errors.push(e);
+                        None
+                    }
+                };
+
+                let run = ScheduleRun {
+                    started_at: search.started_at,
+                    finished_at,
+                    product_count: search.products.len(),
+                    errors: search.errors,
+                    snapshot,
+                };
+                self.finish(&name, run.clone());
+
+                Some(CompletedRun {
+                    schedule_name: name,
+                    query: search.query,
+                    products: search.products,
+                    run,
+                })
+            })
+            .collect()
+    }
+
+    /// Olvida el estado de programaciones que ya no existen
+    pub fn forget(&mut self, schedule_name: &str) {
+        if self.last_runs.remove(schedule_name).is_some() {
+            self.save_state();
+        }
+    }
+
+    fn finish(&mut self, schedule_name: &str, run: ScheduleRun) {
+        self.last_runs.insert(schedule_name.to_string(), run);
+        self.save_state();
+    }
+
+    fn save_state(&self) {
+        if let Err(e) = self.file_manager.save_schedule_state(&self.last_runs) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Tiendas de la programación por nombre; sin lista, las habilitadas
+fn select_stores(schedule: &ScheduledSearch, store_manager: &StoreManager) -> Vec<StoreConfig> {
+    if schedule.stores.is_empty() {
+        return store_manager.get_enabled_stores().into_iter().cloned().collect();
+    }
+
+    store_manager
+        .stores
+        .iter()
+        .filter(|store| schedule.stores.iter().any(|name| name.eq_ignore_ascii_case(&store.name)))
+        .cloned()
+        .collect()
+}
@@ -1,106 +1,391 @@
-use crate::models::{Product, StoreConfig};
+use crate::models::{ExtractionMode, FieldSpec, PaginationMode, Price, Product, StoreConfig};
+use crate::scraping::cache::ResponseCache;
+use crate::scraping::cookies::{CookieJars, StoredCookie};
+use crate::scraping::error::ScrapeError;
+use crate::scraping::http::{HttpClient, HttpSettings};
+use crate::scraping::images::{ImageCache, MAX_IMAGE_BYTES};
+use crate::scraping::robots::{RobotsCache, RobotsPolicy};
 use crate::scraping::selectors::SelectorHelper;
+use crate::scraping::structured::StructuredDataExtractor;
+use crate::utils::{AppConfig, FileManager};
 use scraper::{Html, Selector};
+use std::collections::HashSet;
 
-pub struct WebScraper;
+pub struct WebScraper {
+    http: HttpClient,
+    images: ImageCache,
+    robots: RobotsCache,
+    robots_policy: RobotsPolicy,
+    // 0 significa sin límite
+    max_products_per_store: usize,
+}
+
+/// Productos de una página de resultados y el enlace a la siguiente, si lo hay
+struct ScrapedPage {
+    products: Vec<Product>,
+    next_url: Option<String>,
+}
 
 impl WebScraper {
     pub fn new() -> Self {
-        Self
+        Self::with_config(&AppConfig::default())
+    }
+
+    /// Crea un scraper con los ajustes HTTP de la configuración de la aplicación
+    pub fn with_config(config: &AppConfig) -> Self {
+        // La caché siempre está disponible; `HttpSettings::use_cache` decide si se usa
+        let http = HttpClient::new(HttpSettings::from_app_config(config))
+            .with_cache(ResponseCache::new(FileManager::HTTP_CACHE_DIR, &config.cache))
+            .with_cookies(CookieJars::new(FileManager::COOKIES_DIR));
+
+        Self {
+            http,
+            images: ImageCache::new(FileManager::IMAGE_CACHE_DIR, config.image_cache_mb),
+            robots: RobotsCache::new(),
+            robots_policy: config.robots_policy,
+            max_products_per_store: config.max_products_per_store,
+        }
+    }
+
+    /// Scraper sin esperas, sin robots.txt y sin tarros de cookies en disco
+    #[cfg(test)]
+    pub(crate) fn for_tests(max_products_per_store: usize) -> Self {
+        let settings = HttpSettings {
+            request_delay: std::time::Duration::ZERO,
+            rate_limit: crate::scraping::RateLimitConfig {
+                requests_per_second: 0.0,
+                burst: 1,
+                max_in_flight: 0,
+            },
+            ..HttpSettings::default()
+        };
+        Self {
+            http: HttpClient::new(settings),
+            images: ImageCache::new(std::env::temp_dir().join("yam-webs-scraper-images"), 1),
+            robots: RobotsCache::new(),
+            robots_policy: RobotsPolicy::Ignore,
+            max_products_per_store,
+        }
     }
 
     /// Realiza scraping de múltiples productos en una página
-    pub fn scrape_products(&self, url: &str, store_config: &StoreConfig) -> Result<Vec<Product>, String> {
-        let html = self.fetch_html(url)?;
+    pub fn scrape_products(&self, url: &str, store_config: &StoreConfig) -> Result<Vec<Product>, ScrapeError> {
+        self.scrape_page(url, store_config).map(|page| page.products)
+    }
+
+    /// Descarga una página de resultados y extrae sus productos y el enlace siguiente
+    fn scrape_page(&self, url: &str, store_config: &StoreConfig) -> Result<ScrapedPage, ScrapeError> {
+        let html = self.fetch_html(url, store_config)?;
         let document = Html::parse_document(&html);
+        let mode = store_config.extraction_mode;
+
         let mut products = Vec::new();
+        let mut selector_error = None;
+
+        if mode != ExtractionMode::StructuredData {
+            match self.extract_with_selectors(&document, store_config, url) {
+                Ok(found) => products = found,
+                Err(e) => selector_error = Some(e),
+            }
+        }
+
+        // Datos estructurados como modo propio o como respaldo si los selectores no encontraron nada
+        if mode != ExtractionMode::Css && products.is_empty() {
+            products = StructuredDataExtractor::new(store_config, url).extract(&document);
+        }
+
+        if products.is_empty()
+            && let Some(e) = selector_error
+        {
+            return Err(e);
+        }
 
+        // Una página sin productos puede ser en realidad un captcha o un bloqueo
+        if products.is_empty()
+            && let Some(reason) = detect_block_page(&document)
+        {
+            return Err(ScrapeError::Blocked {
+                url: url.to_string(),
+                reason: reason.to_string(),
+            });
+        }
+
+        let next_url = if store_config.pagination.mode == PaginationMode::NextLink {
+            SelectorHelper::new()
+                .extract_attribute(&document.root_element(), &store_config.pagination.next_page_selector, "href")
+                .map(|href| self.resolve_url(url, &href))
+        } else {
+            None
+        };
+
+        for product in &mut products {
+            product.page_url = Some(url.to_string());
+        }
+
+        Ok(ScrapedPage { products, next_url })
+    }
+
+    /// Aplica los selectores CSS de la tienda sobre cada contenedor de producto
+    pub(crate) fn extract_with_selectors(
+        &self,
+        document: &Html,
+        store_config: &StoreConfig,
+        url: &str,
+    ) -> Result<Vec<Product>, ScrapeError> {
         // Selector para encontrar todos los contenedores de productos
         let container_selector = match Selector::parse(&store_config.product_container_selector) {
             Ok(selector) => selector,
-            Err(e) => return Err(format!("Error en selector de contenedor: {}", e)),
+            Err(e) => {
+                return Err(ScrapeError::Selector {
+                    field: "contenedor".to_string(),
+                    selector: store_config.product_container_selector.clone(),
+                    message: e.to_string(),
+                });
+            }
         };
 
-        // Iterar sobre cada producto encontrado
-        for product_element in document.select(&container_selector) {
-            if let Some(product) = self.extract_product_data(&product_element, store_config, url) {
-                products.push(product);
+        // Un selector o una expresión regular inválidos dejarían el campo vacío en todos
+        // los productos
+        for (field, spec) in store_config.field_specs() {
+            if let Err(message) = spec.validate() {
+                return Err(ScrapeError::Selector {
+                    field: field.to_lowercase(),
+                    selector: spec.selector.clone(),
+                    message,
+                });
             }
         }
 
-        Ok(products)
+        // Iterar sobre cada producto encontrado
+        document
+            .select(&container_selector)
+            .filter_map(|element| self.extract_product_data(&element, store_config, url).transpose())
+            .collect()
     }
 
     /// Realiza scraping de un solo producto
-    pub fn scrape_single_product(&self, url: &str, store_config: &StoreConfig) -> Result<Option<Product>, String> {
-        let html = self.fetch_html(url)?;
+    pub fn scrape_single_product(&self, url: &str, store_config: &StoreConfig) -> Result<Option<Product>, ScrapeError> {
+        let html = self.fetch_html(url, store_config)?;
         let document = Html::parse_document(&html);
+        let mode = store_config.extraction_mode;
 
-        if let Some(product) = self.extract_product_data(&document.root_element(), store_config, url) {
-            Ok(Some(product))
+        let (product, selector_error) = if mode == ExtractionMode::StructuredData {
+            (None, None)
         } else {
-            Ok(None)
+            match self.extract_product_data(&document.root_element(), store_config, url) {
+                Ok(product) => (product, None),
+                Err(e) => (None, Some(e)),
+            }
+        };
+
+        // Igual que en las búsquedas, los datos estructurados sirven de respaldo
+        if product.is_none() && mode != ExtractionMode::Css {
+            let structured = StructuredDataExtractor::new(store_config, url)
+                .extract(&document)
+                .into_iter()
+                .next();
+            if structured.is_some() {
+                return Ok(structured);
+            }
+        }
+
+        match selector_error {
+            Some(e) => Err(e),
+            None => Ok(product),
         }
     }
 
-    /// Busca productos usando el término de búsqueda
-    pub fn search_products(&self, query: &str, store_config: &StoreConfig) -> Result<Vec<Product>, String> {
-        let search_url = store_config.build_search_url(query);
-        self.scrape_products(&search_url, store_config)
+    /// Busca productos usando el término de búsqueda, recorriendo páginas si la tienda lo permite.
+    /// Se detiene al llegar al máximo de productos, al límite de páginas o a una página vacía.
+    pub fn search_products(&self, query: &str, store_config: &StoreConfig) -> Result<Vec<Product>, ScrapeError> {
+        let pagination = &store_config.pagination;
+        let mut products: Vec<Product> = Vec::new();
+        let mut visited_urls = HashSet::new();
+        let mut seen_products = HashSet::new();
+        let mut next_url = Some(store_config.build_search_url(query));
+
+        for page_index in 0..pagination.page_limit() {
+            let url = match pagination.mode {
+                PaginationMode::UrlPattern => store_config.build_page_url(query, page_index),
+                _ => match next_url.take() {
+                    Some(url) => url,
+                    None => break,
+                },
+            };
+
+            // Evita ciclos cuando el enlace "siguiente" apunta a una página ya visitada
+            if !visited_urls.insert(url.clone()) {
+                break;
+            }
+
+            let page = match self.scrape_page(&url, store_config) {
+                Ok(page) => page,
+                // Un fallo en páginas posteriores no invalida lo ya obtenido
+                Err(e) if page_index > 0 => {
+                    eprintln!("Error en la página {} de {}: {}", page_index + 1, store_config.name, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            // Algunas tiendas ignoran el número de página y repiten los mismos resultados
+            let new_products: Vec<Product> = page
+                .products
+                .into_iter()
+                .filter(|p| seen_products.insert((p.url.clone(), p.name.clone())))
+                .collect();
+
+            if new_products.is_empty() {
+                break;
+            }
+
+            products.extend(new_products);
+
+            if self.max_products_per_store > 0 && products.len() >= self.max_products_per_store {
+                products.truncate(self.max_products_per_store);
+                break;
+            }
+
+            next_url = page.next_url;
+        }
+
+        Ok(products)
     }
 
-    /// Obtiene el HTML de una URL
-    fn fetch_html(&self, url: &str) -> Result<String, String> {
-        match reqwest::blocking::get(url) {
-            Ok(response) => {
-                if response.status().is_success() {
-                    match response.text() {
-                        Ok(html) => Ok(html),
-                        Err(e) => Err(format!("Error al leer el contenido: {}", e)),
-                    }
-                } else {
-                    Err(format!("Error HTTP: {}", response.status()))
+    /// Obtiene el HTML de una URL con los ajustes HTTP de la tienda, respetando robots.txt
+    pub(crate) fn fetch_html(&self, url: &str, store_config: &StoreConfig) -> Result<String, ScrapeError> {
+        self.fetch_html_with(url, store_config, self.page_settings(store_config))
+    }
+
+    /// Ajustes HTTP de las páginas de una tienda, con su tarro de cookies
+    pub(crate) fn page_settings(&self, store_config: &StoreConfig) -> HttpSettings {
+        let mut settings = self.http.settings_for(&store_config.http);
+        settings.cookie_jar = Some(store_config.name.clone());
+        settings
+    }
+
+    /// Como `fetch_html`, con unos ajustes ya preparados
+    pub(crate) fn fetch_html_with(
+        &self,
+        url: &str,
+        store_config: &StoreConfig,
+        mut settings: HttpSettings,
+    ) -> Result<String, ScrapeError> {
+        if self.robots_policy != RobotsPolicy::Ignore && !store_config.ignore_robots {
+            let verdict = self.robots.check(&self.http, url, &settings);
+
+            if !verdict.allowed {
+                if self.robots_policy == RobotsPolicy::Enforce {
+                    return Err(ScrapeError::Robots { url: url.to_string() });
                 }
+                eprintln!("Aviso: robots.txt no permite acceder a {}", url);
             }
-            Err(e) => Err(format!("Error de conexión: {}", e)),
+
+            if let Some(crawl_delay) = verdict.crawl_delay {
+                settings.request_delay = settings.request_delay.max(crawl_delay);
+            }
+        }
+
+        self.http.get_text(url, &settings)
+    }
+
+    /// Cookies guardadas de una tienda
+    pub fn stored_cookies(&self, store_name: &str) -> Vec<StoredCookie> {
+        self.http.cookies().map(|jars| jars.list(store_name)).unwrap_or_default()
+    }
+
+    pub fn remove_cookie(&self, store_name: &str, cookie: &StoredCookie) -> Result<(), String> {
+        match self.http.cookies() {
+            Some(jars) => jars.remove(store_name, cookie),
+            None => Ok(()),
+        }
+    }
+
+    /// Mueve las cookies de una tienda que cambió de nombre
+    pub fn rename_cookie_jar(&self, old_name: &str, new_name: &str) -> Result<(), String> {
+        match self.http.cookies() {
+            Some(jars) => jars.rename(old_name, new_name),
+            None => Ok(()),
+        }
+    }
+
+    /// Borra todas las cookies de una tienda; las iniciales se vuelven a poner en la
+    /// siguiente petición
+    pub fn clear_cookies(&self, store_name: &str) -> Result<(), String> {
+        match self.http.cookies() {
+            Some(jars) => jars.clear(store_name),
+            None => Ok(()),
+        }
+    }
+
+    /// Ajustes para descargar las imágenes de una tienda: su proxy y su límite de
+    /// velocidad, como las páginas. Sin tienda se usan los globales.
+    pub fn image_settings(&self, store_config: Option<&StoreConfig>) -> HttpSettings {
+        match store_config {
+            Some(store_config) => self.http.settings_for(&store_config.http),
+            None => self.http.defaults().clone(),
+        }
+    }
+
+    /// Descarga la imagen de un producto, sirviéndola desde la caché en disco si ya está
+    pub fn fetch_image(&self, url: &str, settings: &HttpSettings) -> Result<Vec<u8>, ScrapeError> {
+        if let Some(bytes) = self.images.get(url) {
+            return Ok(bytes);
+        }
+
+        let bytes = self.http.get_bytes(url, settings, MAX_IMAGE_BYTES)?;
+        // Evita guardar páginas de error servidas con código 200
+        if image::guess_format(&bytes).is_err() {
+            return Err(ScrapeError::Decode {
+                url: url.to_string(),
+                message: "el contenido no es una imagen".to_string(),
+            });
+        }
+
+        if let Err(e) = self.images.store(url, &bytes) {
+            eprintln!("{}", e);
         }
+        Ok(bytes)
     }
 
-    /// Extrae los datos de un producto desde un elemento HTML
+    /// Extrae los datos de un producto desde un elemento HTML; `None` si le falta el
+    /// nombre o el precio
     fn extract_product_data(
         &self,
         element: &scraper::ElementRef,
         store_config: &StoreConfig,
         base_url: &str,
-    ) -> Option<Product> {
+    ) -> Result<Option<Product>, ScrapeError> {
         let helper = SelectorHelper::new();
+        let field = |name: &str, spec: &FieldSpec| helper.extract_field(element, name, spec);
 
         // Extraer información básica
-        let name = helper.extract_text(element, &store_config.name_selector)?;
-        let price = helper.extract_text(element, &store_config.price_selector)?;
-        
+        let Some(name) = field("Nombre", &store_config.name_selector)? else {
+            return Ok(None);
+        };
+        let Some(price) = field("Precio", &store_config.price_selector)? else {
+            return Ok(None);
+        };
+
         // Extraer URL del producto
-        let product_url = helper
-            .extract_attribute(element, &store_config.link_selector, "href")
+        let product_url = field("Enlace", &store_config.link_selector)?
             .map(|url| self.resolve_url(base_url, &url))
             .unwrap_or_else(|| base_url.to_string());
 
         // Extraer imagen
-        let image_url = helper
-            .extract_attribute(element, &store_config.image_selector, "src")
+        let image_url = field("Imagen", &store_config.image_selector)?
             .map(|url| self.resolve_url(base_url, &url))
             .unwrap_or_default();
 
         // Extraer descripción si está configurada
-        let description = if let Some(desc_selector) = &store_config.description_selector {
-            helper.extract_text(element, desc_selector)
-        } else {
-            None
+        let description = match &store_config.description_selector {
+            Some(desc_spec) => field("Descripción", desc_spec)?,
+            None => None,
         };
 
         let mut product = Product::new(
             name,
-            price,
+            Price::parse(&price, &store_config.price_locale),
             product_url,
             image_url,
             store_config.name.clone(),
@@ -110,30 +395,321 @@ impl WebScraper {
             product = product.with_description(desc);
         }
 
-        Some(product)
+        for field in store_config.custom_fields.iter().filter(|field| !field.name.trim().is_empty()) {
+            let extracted = helper.extract_field(element, field.name.trim(), &field.spec)?;
+            if let Some(value) = field.to_value(extracted, &store_config.price_locale) {
+                product.extra.insert(field.name.trim().to_string(), value);
+            }
+        }
+
+        Ok(Some(product))
     }
 
-    /// Resuelve URLs relativas a absolutas
+    /// Resuelve URLs relativas a absolutas, conservando el puerto de la página
     fn resolve_url(&self, base_url: &str, relative_url: &str) -> String {
-        if relative_url.starts_with("http") {
-            relative_url.to_string()
-        } else if relative_url.starts_with("//") {
-            format!("https:{}", relative_url)
-        } else if relative_url.starts_with('/') {
-            // Extraer el dominio base
-            if let Ok(parsed_base) = reqwest::Url::parse(base_url) {
-                format!("{}://{}{}", parsed_base.scheme(), parsed_base.host_str().unwrap_or(""), relative_url)
-            } else {
-                relative_url.to_string()
-            }
-        } else {
-            format!("{}/{}", base_url.trim_end_matches('/'), relative_url)
+        match reqwest::Url::parse(base_url).and_then(|base| base.join(relative_url.trim())) {
+            Ok(url) => url.to_string(),
+            Err(_) => relative_url.to_string(),
+        }
+    }
+}
+
+/// Busca señales típicas de páginas de captcha o anti-bots. Sólo cuentan el título, el
+/// texto visible y los formularios de desafío conocidos: un script de reCAPTCHA o la
+/// palabra "captcha" en el HTML de una página normal sin resultados no es un bloqueo.
+fn detect_block_page(document: &Html) -> Option<&'static str> {
+    const INTERSTITIALS: &[(&str, &str)] = &[
+        ("#challenge-form", "desafío de Cloudflare"),
+        ("#cf-challenge-running", "desafío de Cloudflare"),
+        (".cf-browser-verification", "desafío de Cloudflare"),
+        ("form#captcha-form", "captcha"),
+    ];
+    const TITLE_MARKERS: &[(&str, &str)] = &[
+        ("just a moment", "desafío de Cloudflare"),
+        ("attention required", "desafío de Cloudflare"),
+        ("captcha", "captcha"),
+        ("robot check", "verificación anti-robots"),
+        ("access denied", "acceso denegado"),
+        ("acceso denegado", "acceso denegado"),
+    ];
+    const TEXT_MARKERS: &[(&str, &str)] = &[
+        ("are you a robot", "verificación anti-robots"),
+        ("verify you are human", "verificación anti-robots"),
+        ("no eres un robot", "verificación anti-robots"),
+        ("enter the characters you see below", "captcha"),
+    ];
+
+    for (selector, reason) in INTERSTITIALS {
+        if let Ok(selector) = Selector::parse(selector)
+            && document.select(&selector).next().is_some()
+        {
+            return Some(reason);
         }
     }
+
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+        .map(|title| title.text().collect::<String>().to_lowercase())
+        .unwrap_or_default();
+    if let Some((_, reason)) = TITLE_MARKERS.iter().find(|(marker, _)| title.contains(marker)) {
+        return Some(reason);
+    }
+
+    let text = visible_text(document).to_lowercase();
+    TEXT_MARKERS
+        .iter()
+        .find(|(marker, _)| text.contains(marker))
+        .map(|(_, reason)| *reason)
+}
+
+/// Texto que vería el usuario: sin scripts, estilos ni plantillas
+fn visible_text(document: &Html) -> String {
+    const HIDDEN: &[&str] = &["script", "style", "noscript", "template", "head"];
+
+    document
+        .root_element()
+        .descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let hidden = node
+                .ancestors()
+                .filter_map(|ancestor| ancestor.value().as_element())
+                .any(|element| HIDDEN.contains(&element.name()));
+            (!hidden).then_some(&**text)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 impl Default for WebScraper {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scraping::{ProxyConfig, ProxyMode, RateLimitConfig};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Servidor HTTP mínimo en 127.0.0.1: `page` da el HTML de cada ruta y se anota la
+    /// ruta de cada petición
+    fn serve(page: impl Fn(&str) -> String + Send + 'static) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buffer = [0u8; 4096];
+                let read = stream.read(&mut buffer).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..read]);
+                let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+                let body = page(&path);
+                seen.lock().unwrap().push(path);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (address, requests)
+    }
+
+    fn results(names: &[String], next: Option<&str>) -> String {
+        let items: String = names
+            .iter()
+            .map(|name| format!(r#"<div class="item"><a href="/p/{0}">{0}</a><span class="price">10</span></div>"#, name))
+            .collect();
+        let next = next.map(|href| format!(r#"<a class="next" href="{}">Siguiente</a>"#, href)).unwrap_or_default();
+        format!("<html><body>{}{}</body></html>", items, next)
+    }
+
+    fn paginated_store(address: &str, mode: PaginationMode) -> StoreConfig {
+        let mut store = StoreConfig::new("Tienda".to_string(), address.to_string());
+        store.search_url_pattern = "{base_url}/buscar?q={query}&page={page}".to_string();
+        store.product_container_selector = ".item".to_string();
+        store.name_selector = FieldSpec::text("a");
+        store.price_selector = FieldSpec::text(".price");
+        store.link_selector = FieldSpec::attribute("a", "href");
+        store.pagination.mode = mode;
+        store.pagination.max_pages = 10;
+        store.pagination.next_page_selector = "a.next".to_string();
+        store
+    }
+
+    /// Número de página de una ruta "...&page=N"
+    fn page_number(path: &str) -> usize {
+        path.rsplit("page=").next().and_then(|n| n.parse().ok()).unwrap_or(0)
+    }
+
+    fn names(page: usize, count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("p{}-{}", page, i)).collect()
+    }
+
+    fn blocked(html: &str) -> Option<&'static str> {
+        detect_block_page(&Html::parse_document(html))
+    }
+
+    #[test]
+    fn recaptcha_script_on_empty_results_is_not_a_block() {
+        let html = r#"<html><head><title>Resultados para "xyz"</title>
+            <script src="https://www.google.com/recaptcha/api.js"></script></head>
+            <body><p>No encontramos productos.</p><div class="g-recaptcha"></div>
+            <script>var msg = "access denied captcha";</script></body></html>"#;
+        assert_eq!(blocked(html), None);
+    }
+
+    #[test]
+    fn challenge_title_is_a_block() {
+        let html = "<html><head><title>Just a moment...</title></head><body></body></html>";
+        assert_eq!(blocked(html), Some("desafío de Cloudflare"));
+    }
+
+    #[test]
+    fn visible_robot_question_is_a_block() {
+        let html = "<html><head><title>Tienda</title></head><body><h1>Are you a robot?</h1></body></html>";
+        assert_eq!(blocked(html), Some("verificación anti-robots"));
+    }
+
+    #[test]
+    fn images_use_the_store_proxy_and_rate_limit() {
+        let scraper = WebScraper::new();
+        let mut store = StoreConfig::new("Tienda".to_string(), "https://tienda.example".to_string());
+        let proxy = ProxyConfig {
+            mode: ProxyMode::Manual,
+            url: "socks5h://127.0.0.1:1080".to_string(),
+            ..ProxyConfig::default()
+        };
+        let rate_limit = RateLimitConfig {
+            requests_per_second: 0.5,
+            burst: 1,
+            max_in_flight: 1,
+        };
+        store.http.proxy = Some(proxy.clone());
+        store.http.rate_limit = Some(rate_limit.clone());
+
+        let settings = scraper.image_settings(Some(&store));
+        assert_eq!(settings.proxy, proxy);
+        assert_eq!(settings.rate_limit, rate_limit);
+        assert_eq!(&scraper.image_settings(None), scraper.http.defaults());
+    }
+
+    #[test]
+    fn challenge_form_is_a_block() {
+        let html = r#"<html><body><form id="challenge-form"></form></body></html>"#;
+        assert_eq!(blocked(html), Some("desafío de Cloudflare"));
+    }
+
+    #[test]
+    fn invalid_field_reports_its_selector_or_pattern() {
+        let scraper = WebScraper::new();
+        let document = Html::parse_document(r#"<div class="item"><b class="n">Taladro</b><i>10</i></div>"#);
+        let mut store = StoreConfig::new("Tienda".to_string(), "https://tienda.example".to_string());
+        store.product_container_selector = ".item".to_string();
+        store.name_selector = FieldSpec::text(".n");
+        store.price_selector = FieldSpec::text("i[");
+        assert!(!store.is_valid());
+
+        match scraper.extract_with_selectors(&document, &store, "https://tienda.example") {
+            Err(ScrapeError::Selector { field, selector, .. }) => {
+                assert_eq!((field.as_str(), selector.as_str()), ("precio", "i["));
+            }
+            other => panic!("se esperaba un error de selector: {:?}", other),
+        }
+
+        store.price_selector = FieldSpec::text("i");
+        store.price_selector.regex = Some("(sin cerrar".to_string());
+        match scraper.extract_with_selectors(&document, &store, "https://tienda.example") {
+            Err(ScrapeError::Selector { selector, message, .. }) => {
+                assert_eq!(selector, "i");
+                assert!(message.contains("Expresión regular"));
+            }
+            other => panic!("se esperaba un error de selector: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn page_url_substitutes_page_and_offset() {
+        let mut store = StoreConfig::new("Tienda".to_string(), "https://tienda.example".to_string());
+        store.search_url_pattern = "{base_url}/buscar?q={query}&page={page}&desde={offset}".to_string();
+        store.pagination.first_page = 1;
+        store.pagination.page_size = 24;
+        assert_eq!(store.build_page_url("taladro", 0), "https://tienda.example/buscar?q=taladro&page=1&desde=0");
+        assert_eq!(store.build_page_url("taladro", 2), "https://tienda.example/buscar?q=taladro&page=3&desde=48");
+        assert_eq!(store.build_search_url("taladro"), store.build_page_url("taladro", 0));
+    }
+
+    #[test]
+    fn url_pattern_stops_on_an_empty_page() {
+        let (address, requests) = serve(|path| {
+            let page = page_number(path);
+            results(&if page <= 2 { names(page, 2) } else { Vec::new() }, None)
+        });
+        let store = paginated_store(&address, PaginationMode::UrlPattern);
+
+        let products = WebScraper::for_tests(0).search_products("taladro", &store).unwrap();
+        assert_eq!(products.len(), 4);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(products[0].url, format!("{}/p/p1-0", address));
+    }
+
+    #[test]
+    fn url_pattern_stops_when_the_page_repeats() {
+        // La tienda ignora el número de página
+        let (address, requests) = serve(|_| results(&names(1, 3), None));
+        let store = paginated_store(&address, PaginationMode::UrlPattern);
+
+        let products = WebScraper::for_tests(0).search_products("taladro", &store).unwrap();
+        assert_eq!(products.len(), 3);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn page_limit_and_product_cap() {
+        let (address, requests) = serve(|path| results(&names(page_number(path), 2), None));
+        let mut store = paginated_store(&address, PaginationMode::UrlPattern);
+        store.pagination.max_pages = 3;
+        assert_eq!(WebScraper::for_tests(0).search_products("taladro", &store).unwrap().len(), 6);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        requests.lock().unwrap().clear();
+        store.pagination.max_pages = 10;
+        assert_eq!(WebScraper::for_tests(3).search_products("taladro", &store).unwrap().len(), 3);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn next_link_is_followed_until_it_loops() {
+        let (address, requests) = serve(|path| match path {
+            "/buscar?q=taladro&page=1" => results(&names(1, 2), Some("/siguiente")),
+            "/siguiente" => results(&names(2, 2), Some("/buscar?q=taladro&page=1")),
+            _ => results(&[], None),
+        });
+        let store = paginated_store(&address, PaginationMode::NextLink);
+
+        let products = WebScraper::for_tests(0).search_products("taladro", &store).unwrap();
+        assert_eq!(products.len(), 4);
+        assert_eq!(
+            requests.lock().unwrap().as_slice(),
+            ["/buscar?q=taladro&page=1", "/siguiente"]
+        );
+    }
+
+    #[test]
+    fn without_pagination_only_the_first_page_is_read() {
+        let (address, requests) = serve(|path| results(&names(page_number(path), 2), Some("/siguiente")));
+        let store = paginated_store(&address, PaginationMode::None);
+
+        assert_eq!(WebScraper::for_tests(0).search_products("taladro", &store).unwrap().len(), 2);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+}
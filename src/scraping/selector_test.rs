@@ -0,0 +1,254 @@
+use crate::models::{ExtractionMode, FieldSpec, Product, StoreConfig};
+use crate::scraping::error::ScrapeError;
+use crate::scraping::selectors::SelectorHelper;
+use crate::scraping::structured::StructuredDataExtractor;
+use crate::scraping::WebScraper;
+use scraper::{ElementRef, Html, Selector};
+
+/// Resultado de probar un selector sobre la página
+#[derive(Debug, Clone)]
+pub struct SelectorCheck {
+    pub field: String,
+    pub selector: String,
+    // Elementos encontrados (dentro de los contenedores, salvo para el propio contenedor)
+    pub matches: usize,
+    // Valor extraído del primer contenedor, ya procesado
+    pub sample: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Informe completo de la herramienta de prueba de selectores
+#[derive(Debug, Clone)]
+pub struct SelectorTestReport {
+    pub url: String,
+    pub checks: Vec<SelectorCheck>,
+    pub products: Vec<Product>,
+    pub used_structured_data: bool,
+}
+
+impl WebScraper {
+    /// Descarga la URL con la configuración indicada (aunque no esté guardada) y
+    /// reporta cuántos elementos encuentra cada selector y qué productos se extraen. Con
+    /// `use_cache`, las pruebas repetidas reutilizan la página mientras no venza la caché.
+    pub fn test_selectors(
+        &self,
+        url: &str,
+        store_config: &StoreConfig,
+        use_cache: bool,
+    ) -> Result<SelectorTestReport, ScrapeError> {
+        let mut settings = self.page_settings(store_config);
+        settings.use_cache = use_cache;
+        let html = self.fetch_html_with(url, store_config, settings)?;
+        let document = Html::parse_document(&html);
+
+        let container_check = check_selector(
+            "Contenedor",
+            &store_config.product_container_selector,
+            &[document.root_element()],
+            true,
+        );
+        let containers: Vec<ElementRef> = Selector::parse(&store_config.product_container_selector)
+            .map(|selector| document.select(&selector).collect())
+            .unwrap_or_default();
+
+        let mut checks = vec![container_check];
+        for (field, spec) in store_config.field_specs() {
+            let required = matches!(field, "Nombre" | "Precio");
+            checks.push(check_field(field, spec, &containers, required));
+        }
+
+        let mode = store_config.extraction_mode;
+        let mut products = if mode == ExtractionMode::StructuredData {
+            Vec::new()
+        } else {
+            self.extract_with_selectors(&document, store_config, url)
+                .unwrap_or_default()
+        };
+
+        let mut used_structured_data = false;
+        if mode != ExtractionMode::Css && products.is_empty() {
+            products = StructuredDataExtractor::new(store_config, url).extract(&document);
+            used_structured_data = !products.is_empty();
+        }
+
+        Ok(SelectorTestReport {
+            url: url.to_string(),
+            checks,
+            products,
+            used_structured_data,
+        })
+    }
+}
+
+/// Cuenta las coincidencias de un selector dentro de los elementos dados
+fn check_selector(field: &str, selector_str: &str, scopes: &[ElementRef], required: bool) -> SelectorCheck {
+    let mut check = SelectorCheck {
+        field: field.to_string(),
+        selector: selector_str.to_string(),
+        matches: 0,
+        sample: None,
+        error: None,
+    };
+
+    if selector_str.trim().is_empty() {
+        if required {
+            check.error = Some("Selector vacío".to_string());
+        }
+        return check;
+    }
+
+    let selector = match Selector::parse(selector_str) {
+        Ok(selector) => selector,
+        Err(e) => {
+            check.error = Some(format!("Selector inválido: {}", e));
+            return check;
+        }
+    };
+
+    check.matches = scopes.iter().map(|scope| scope.select(&selector).count()).sum();
+    if check.matches == 0 {
+        check.error = Some(if scopes.is_empty() {
+            "No hay contenedores donde buscar".to_string()
+        } else {
+            "Sin coincidencias".to_string()
+        });
+    }
+
+    check
+}
+
+/// Como `check_selector`, pero además prueba la expresión regular y extrae un valor de muestra
+fn check_field(field: &str, spec: &FieldSpec, scopes: &[ElementRef], required: bool) -> SelectorCheck {
+    let mut check = check_selector(field, &spec.selector, scopes, required);
+    if check.error.is_some() || spec.is_empty() {
+        return check;
+    }
+
+    if let Err(e) = spec.validate() {
+        check.error = Some(e);
+        return check;
+    }
+
+    let helper = SelectorHelper::new();
+    check.sample = match scopes.first().map(|scope| helper.extract_field(scope, field, spec)) {
+        Some(Ok(sample)) => sample,
+        Some(Err(e)) => {
+            check.error = Some(e.to_string());
+            return check;
+        }
+        None => None,
+    };
+    if check.sample.is_none() && required {
+        check.error = Some("El primer producto no da ningún valor".to_string());
+    }
+
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    const PAGE: &str = r#"<html><body>
+        <div class="item"><a href="/p/1">Taladro</a><span class="price">Precio: 49,90 €</span></div>
+        <div class="item"><a href="/p/2">Sierra</a><span class="price">Precio: 30 €</span></div>
+    </body></html>"#;
+
+    fn containers(document: &Html) -> Vec<ElementRef<'_>> {
+        document.select(&Selector::parse(".item").unwrap()).collect()
+    }
+
+    #[test]
+    fn selector_counts_matches_inside_containers() {
+        let document = Html::parse_document(PAGE);
+        let check = check_selector("Nombre", "a", &containers(&document), true);
+        assert_eq!(check.matches, 2);
+        assert_eq!(check.error, None);
+
+        let check = check_selector("Nombre", "h2", &containers(&document), true);
+        assert_eq!(check.error.as_deref(), Some("Sin coincidencias"));
+
+        let check = check_selector("Nombre", "a", &[], true);
+        assert_eq!(check.error.as_deref(), Some("No hay contenedores donde buscar"));
+    }
+
+    #[test]
+    fn empty_or_invalid_selectors_are_reported() {
+        let document = Html::parse_document(PAGE);
+        let scopes = containers(&document);
+
+        assert_eq!(check_selector("Nombre", " ", &scopes, true).error.as_deref(), Some("Selector vacío"));
+        assert_eq!(check_selector("Imagen", "", &scopes, false).error, None);
+
+        let error = check_selector("Nombre", "a[", &scopes, true).error.unwrap();
+        assert!(error.starts_with("Selector inválido"), "{}", error);
+    }
+
+    #[test]
+    fn field_sample_comes_from_the_first_container() {
+        let document = Html::parse_document(PAGE);
+        let scopes = containers(&document);
+
+        let mut spec = FieldSpec::text(".price");
+        spec.regex = Some(r"(\d+,\d+)".to_string());
+        let check = check_field("Precio", &spec, &scopes, true);
+        assert_eq!(check.matches, 2);
+        assert_eq!(check.sample.as_deref(), Some("49,90"));
+        assert_eq!(check.error, None);
+
+        let check = check_field("Enlace", &FieldSpec::attribute("a", "href"), &scopes, false);
+        assert_eq!(check.sample.as_deref(), Some("/p/1"));
+    }
+
+    #[test]
+    fn field_without_sample_or_with_a_bad_pattern_fails() {
+        let document = Html::parse_document(PAGE);
+        let scopes = containers(&document);
+
+        let check = check_field("Nombre", &FieldSpec::attribute("a", "title"), &scopes, true);
+        assert_eq!(check.sample, None);
+        assert_eq!(check.error.as_deref(), Some("El primer producto no da ningún valor"));
+
+        let mut spec = FieldSpec::text(".price");
+        spec.regex = Some("(sin cerrar".to_string());
+        assert!(check_field("Precio", &spec, &scopes, true).error.is_some());
+    }
+
+    #[test]
+    fn report_checks_every_field_and_extracts_products() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+        thread::spawn(move || {
+            if let Some(mut stream) = listener.incoming().flatten().next() {
+                let _ = stream.read(&mut [0u8; 4096]);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    PAGE.len(),
+                    PAGE
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let mut store = StoreConfig::new("Tienda".to_string(), address.clone());
+        store.product_container_selector = ".item".to_string();
+        store.name_selector = FieldSpec::text("a");
+        store.price_selector = FieldSpec::text(".price");
+        store.link_selector = FieldSpec::attribute("a", "href");
+        store.image_selector = FieldSpec::attribute("img", "src");
+
+        let report = WebScraper::for_tests(0)
+            .test_selectors(&format!("{}/buscar", address), &store, false)
+            .unwrap();
+
+        let check = |field: &str| report.checks.iter().find(|c| c.field == field).unwrap();
+        assert_eq!(check("Contenedor").matches, 2);
+        assert_eq!(check("Nombre").sample.as_deref(), Some("Taladro"));
+        assert_eq!(check("Imagen").error.as_deref(), Some("Sin coincidencias"));
+        assert_eq!(report.products.len(), 2);
+        assert!(!report.used_structured_data);
+    }
+}
@@ -1,4 +1,6 @@
-use scraper::{ElementRef, Selector};
+use crate::models::{FieldSource, FieldSpec};
+use crate::scraping::ScrapeError;
+use scraper::{ElementRef, Node, Selector};
 
 pub struct SelectorHelper;
 
@@ -42,6 +44,48 @@ impl SelectorHelper {
         }
     }
 
+    /// Extrae un campo según su especificación: primer elemento que coincide, origen del
+    /// valor, captura con la expresión regular y transformaciones. Sólo falla si el
+    /// selector o la expresión regular son inválidos.
+    pub fn extract_field(
+        &self,
+        element: &ElementRef,
+        field: &str,
+        spec: &FieldSpec,
+    ) -> Result<Option<String>, ScrapeError> {
+        if spec.is_empty() {
+            return Ok(None);
+        }
+
+        let invalid = |message: String| ScrapeError::Selector {
+            field: field.to_lowercase(),
+            selector: spec.selector.clone(),
+            message,
+        };
+        let selector = Selector::parse(&spec.selector).map_err(|e| invalid(e.to_string()))?;
+        let Some(selected_element) = element.select(&selector).next() else {
+            return Ok(None);
+        };
+
+        let raw = match &spec.source {
+            FieldSource::Text => selected_element.text().collect::<String>(),
+            FieldSource::OwnText => selected_element
+                .children()
+                .filter_map(|child| match child.value() {
+                    Node::Text(text) => Some(&**text),
+                    _ => None,
+                })
+                .collect::<String>(),
+            FieldSource::Attribute(name) => match selected_element.value().attr(name) {
+                Some(value) => value.to_string(),
+                None => return Ok(None),
+            },
+            FieldSource::InnerHtml => selected_element.inner_html(),
+        };
+
+        spec.process(raw).map_err(invalid)
+    }
+
     /// Extrae múltiples textos de elementos usando un selector CSS
     pub fn extract_multiple_texts(&self, element: &ElementRef, selector_str: &str) -> Vec<String> {
         if selector_str.is_empty() {
@@ -0,0 +1,558 @@
+use crate::models::{Price, PriceLocale, Product, StoreConfig};
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use url::Url;
+
+// Propiedades schema.org que pueden llevar el código de barras
+const GTIN_KEYS: [&str; 5] = ["gtin13", "gtin", "gtin12", "gtin14", "gtin8"];
+
+/// Extrae productos de datos estructurados schema.org (JSON-LD y microdata)
+pub struct StructuredDataExtractor<'a> {
+    store_config: &'a StoreConfig,
+    page_url: &'a str,
+}
+
+impl<'a> StructuredDataExtractor<'a> {
+    pub fn new(store_config: &'a StoreConfig, page_url: &'a str) -> Self {
+        Self {
+            store_config,
+            page_url,
+        }
+    }
+
+    /// Todos los productos encontrados en la página; JSON-LD tiene prioridad sobre microdata
+    pub fn extract(&self, document: &Html) -> Vec<Product> {
+        let products = self.extract_json_ld(document);
+        if !products.is_empty() {
+            return products;
+        }
+        self.extract_microdata(document)
+    }
+
+    /// Lee los bloques `<script type="application/ld+json">`
+    pub fn extract_json_ld(&self, document: &Html) -> Vec<Product> {
+        let Ok(selector) = Selector::parse(r#"script[type="application/ld+json"]"#) else {
+            return Vec::new();
+        };
+
+        let mut products = Vec::new();
+        for script in document.select(&selector) {
+            let content = script.text().collect::<String>();
+            match serde_json::from_str::<Value>(content.trim()) {
+                Ok(value) => self.collect_json_ld(&value, &mut products),
+                Err(e) => eprintln!("JSON-LD inválido en {}: {}", self.page_url, e),
+            }
+        }
+        products
+    }
+
+    /// Recorre el JSON buscando nodos Product, también dentro de @graph e ItemList
+    fn collect_json_ld(&self, value: &Value, products: &mut Vec<Product>) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.collect_json_ld(item, products);
+                }
+            }
+            Value::Object(object) => {
+                if has_type(value, "Product") {
+                    if let Some(product) = self.product_from_json_ld(value) {
+                        products.push(product);
+                    }
+                    return;
+                }
+
+                if has_type(value, "ItemList") {
+                    if let Some(Value::Array(elements)) = object.get("itemListElement") {
+                        for element in elements {
+                            // Un ListItem envuelve el producto en "item"
+                            self.collect_json_ld(element.get("item").unwrap_or(element), products);
+                        }
+                    }
+                    return;
+                }
+
+                if let Some(graph) = object.get("@graph") {
+                    self.collect_json_ld(graph, products);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn product_from_json_ld(&self, node: &Value) -> Option<Product> {
+        let name = json_text(node.get("name")?)?;
+        let (price, currency) = node.get("offers").and_then(offer_price).unwrap_or_default();
+
+        let url = node
+            .get("url")
+            .and_then(json_text)
+            .or_else(|| node.get("offers").and_then(|o| first(o).get("url")).and_then(json_text))
+            .map(|u| self.resolve(&u))
+            .unwrap_or_else(|| self.page_url.to_string());
+
+        let image_url = node
+            .get("image")
+            .and_then(image_url)
+            .map(|u| self.resolve(&u))
+            .unwrap_or_default();
+
+        let mut product = Product::new(
+            name,
+            self.build_price(&price, currency),
+            url,
+            image_url,
+            self.store_config.name.clone(),
+        );
+
+        if let Some(description) = node.get("description").and_then(json_text) {
+            product = product.with_description(description);
+        }
+
+        product.gtin = GTIN_KEYS.iter().find_map(|key| node.get(*key).and_then(json_text));
+        product.sku = node.get("sku").and_then(json_text);
+        // brand puede ser texto o un objeto Brand/Organization con name
+        product.brand = node
+            .get("brand")
+            .and_then(|brand| first(brand).get("name").and_then(json_text).or_else(|| json_text(brand)));
+
+        Some(product)
+    }
+
+    /// Lee elementos con `itemtype` de schema.org/Product y sus `itemprop`. Sólo cuentan
+    /// los productos sueltos y las entradas de un ItemList; un producto anidado en otro
+    /// (isRelatedTo, isAccessoryOrSparePartFor...) no es un resultado.
+    pub fn extract_microdata(&self, document: &Html) -> Vec<Product> {
+        let Ok(selector) = Selector::parse(r#"[itemscope][itemtype*="schema.org/Product"]"#) else {
+            return Vec::new();
+        };
+
+        document
+            .select(&selector)
+            .filter(is_listed_product)
+            .filter_map(|element| self.product_from_microdata(&element))
+            .collect()
+    }
+
+    fn product_from_microdata(&self, element: &ElementRef) -> Option<Product> {
+        let name = itemprop(element, "name")?;
+
+        // El precio suele ir en un Offer anidado; algunos sitios lo ponen directo en el producto
+        let offer = scoped_property(element, "offers").filter(|offer| offer.value().attr("itemscope").is_some());
+        let scope = offer.as_ref().unwrap_or(element);
+        // En AggregateOffer el precio va en lowPrice
+        let price = itemprop_value(scope, "price")
+            .or_else(|| itemprop_value(scope, "lowPrice"))
+            .or_else(|| itemprop_value(element, "price"))
+            .unwrap_or_default();
+        let currency = itemprop(scope, "priceCurrency").or_else(|| itemprop(element, "priceCurrency"));
+
+        let url = itemprop(element, "url")
+            .map(|u| self.resolve(&u))
+            .unwrap_or_else(|| self.page_url.to_string());
+        let image_url = itemprop(element, "image")
+            .map(|u| self.resolve(&u))
+            .unwrap_or_default();
+
+        let mut product = Product::new(
+            name,
+            self.build_microdata_price(price, currency),
+            url,
+            image_url,
+            self.store_config.name.clone(),
+        );
+
+        if let Some(description) = itemprop(element, "description") {
+            product = product.with_description(description);
+        }
+
+        product.gtin = GTIN_KEYS.iter().find_map(|key| itemprop(element, key));
+        product.sku = itemprop(element, "sku");
+        product.brand = itemprop(element, "brand");
+
+        Some(product)
+    }
+
+    /// Precio de schema.org: en JSON-LD usa siempre punto decimal y la moneda viene aparte
+    fn build_price(&self, amount: &str, currency: Option<String>) -> Price {
+        let locale = PriceLocale {
+            decimal_separator: Some('.'),
+            thousands_separator: None,
+            default_currency: self.store_config.price_locale.default_currency.clone(),
+        };
+        self.price_with_locale(amount, currency, &locale)
+    }
+
+    /// En microdata sólo el atributo `content` tiene formato de máquina; el texto visible
+    /// sigue el formato de la tienda ("1.299,99")
+    fn build_microdata_price(&self, (amount, machine): (String, bool), currency: Option<String>) -> Price {
+        if machine {
+            self.build_price(&amount, currency)
+        } else {
+            self.price_with_locale(&amount, currency, &self.store_config.price_locale)
+        }
+    }
+
+    fn price_with_locale(&self, amount: &str, currency: Option<String>, locale: &PriceLocale) -> Price {
+        let raw = match &currency {
+            Some(currency) if !amount.is_empty() => format!("{} {}", amount, currency),
+            _ => amount.to_string(),
+        };
+
+        let mut price = Price::parse(&raw, locale);
+        if currency.is_some() {
+            price.currency = currency;
+        }
+        price
+    }
+
+    fn resolve(&self, url: &str) -> String {
+        Url::parse(self.page_url)
+            .and_then(|base| base.join(url))
+            .map(|u| u.to_string())
+            .unwrap_or_else(|_| url.to_string())
+    }
+}
+
+fn has_type(value: &Value, wanted: &str) -> bool {
+    let matches = |t: &Value| {
+        t.as_str()
+            .is_some_and(|s| s == wanted || s.ends_with(&format!("/{}", wanted)))
+    };
+
+    match value.get("@type") {
+        Some(Value::Array(types)) => types.iter().any(matches),
+        Some(t) => matches(t),
+        None => false,
+    }
+}
+
+/// Texto de un valor JSON-LD: cadena, número o el primer elemento de un arreglo
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(items) => items.iter().find_map(json_text),
+        Value::Object(object) => object.get("@value").and_then(json_text),
+        _ => None,
+    }
+}
+
+fn first(value: &Value) -> &Value {
+    match value {
+        Value::Array(items) => items.first().unwrap_or(value),
+        _ => value,
+    }
+}
+
+/// Precio y moneda de un Offer, AggregateOffer o arreglo de ofertas (se toma la más barata)
+fn offer_price(offers: &Value) -> Option<(String, Option<String>)> {
+    match offers {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(offer_price)
+            .min_by(|a, b| {
+                let parse = |s: &str| s.parse::<f64>().unwrap_or(f64::MAX);
+                parse(&a.0).total_cmp(&parse(&b.0))
+            }),
+        Value::Object(_) => {
+            let price = ["price", "lowPrice", "highPrice"]
+                .iter()
+                .find_map(|key| offers.get(*key).and_then(json_text))
+                .or_else(|| {
+                    offers
+                        .get("priceSpecification")
+                        .and_then(|spec| first(spec).get("price"))
+                        .and_then(json_text)
+                })
+                .or_else(|| offers.get("offers").and_then(offer_price).map(|(p, _)| p))?;
+
+            let currency = offers
+                .get("priceCurrency")
+                .and_then(json_text)
+                .or_else(|| {
+                    offers
+                        .get("priceSpecification")
+                        .and_then(|spec| first(spec).get("priceCurrency"))
+                        .and_then(json_text)
+                });
+
+            Some((price, currency))
+        }
+        _ => None,
+    }
+}
+
+/// URL de imagen: cadena, arreglo o ImageObject
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(object) => object
+            .get("url")
+            .or_else(|| object.get("contentUrl"))
+            .and_then(json_text),
+        Value::Array(items) => items.iter().find_map(image_url),
+        other => json_text(other),
+    }
+}
+
+/// Producto suelto o entrada de un ItemList (directa o dentro del `item` de un ListItem)
+fn is_listed_product(element: &ElementRef) -> bool {
+    match element.value().attr("itemprop") {
+        None => true,
+        Some(props) => props
+            .split_whitespace()
+            .any(|prop| prop == "itemListElement" || prop == "item"),
+    }
+}
+
+/// Primer elemento con el `itemprop` que pertenece al ámbito del elemento, saltando los
+/// que están dentro de otro `itemscope` anidado (las propiedades de un Brand u Offer no
+/// son del producto)
+fn scoped_property<'b>(element: &ElementRef<'b>, name: &str) -> Option<ElementRef<'b>> {
+    element
+        .descendants()
+        .skip(1)
+        .filter_map(ElementRef::wrap)
+        .find(|found| {
+            found
+                .value()
+                .attr("itemprop")
+                .is_some_and(|props| props.split_whitespace().any(|prop| prop == name))
+                && found
+                    .ancestors()
+                    .take_while(|ancestor| ancestor.id() != element.id())
+                    .filter_map(|ancestor| ancestor.value().as_element())
+                    .all(|ancestor| ancestor.attr("itemscope").is_none())
+        })
+}
+
+/// Valor de un `itemprop` dentro del elemento: atributo content, href, src o el texto
+fn itemprop(element: &ElementRef, name: &str) -> Option<String> {
+    itemprop_value(element, name).map(|(value, _)| value)
+}
+
+/// Como `itemprop`, indicando además si el valor viene en formato de máquina
+/// (`content`, o `value` de `<data>`) o es texto visible
+fn itemprop_value(element: &ElementRef, name: &str) -> Option<(String, bool)> {
+    let found = scoped_property(element, name)?;
+    let value = found.value();
+
+    let machine = value
+        .attr("content")
+        .or_else(|| matches!(value.name(), "data" | "meter").then(|| value.attr("value")).flatten());
+    if let Some(machine) = machine {
+        return Some((machine.trim().to_string(), true)).filter(|(s, _)| !s.is_empty());
+    }
+
+    // Un Brand u Organization anidado aporta su nombre
+    if value.attr("itemscope").is_some() {
+        return itemprop_value(&found, "name");
+    }
+
+    value
+        .attr("href")
+        .or_else(|| value.attr("src"))
+        .map(|s| s.trim().to_string())
+        .or_else(|| Some(found.text().collect::<String>().trim().to_string()))
+        .filter(|s| !s.is_empty())
+        .map(|s| (s, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    const PAGE: &str = "https://tienda.example/buscar?q=taladro";
+
+    fn store() -> StoreConfig {
+        let mut store = StoreConfig::new("Tienda".to_string(), "https://tienda.example".to_string());
+        store.price_locale.decimal_separator = Some(',');
+        store.price_locale.thousands_separator = Some('.');
+        store
+    }
+
+    fn microdata(html: &str) -> Vec<Product> {
+        let store = store();
+        StructuredDataExtractor::new(&store, PAGE).extract_microdata(&Html::parse_document(html))
+    }
+
+    fn json_ld(json: &str) -> Vec<Product> {
+        let store = store();
+        let html = format!(r#"<html><head><script type="application/ld+json">{}</script></head></html>"#, json);
+        StructuredDataExtractor::new(&store, PAGE).extract_json_ld(&Html::parse_document(&html))
+    }
+
+    fn dec(text: &str) -> Option<Decimal> {
+        Some(Decimal::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn visible_microdata_price_uses_store_locale() {
+        let products = microdata(
+            r#"<div itemscope itemtype="https://schema.org/Product">
+                <span itemprop="name">Taladro</span>
+                <div itemprop="offers" itemscope itemtype="https://schema.org/Offer">
+                    <span itemprop="price">1.299,99</span>
+                    <meta itemprop="priceCurrency" content="EUR">
+                </div>
+            </div>"#,
+        );
+        assert_eq!(products.len(), 1);
+        assert_eq!(products[0].price.amount, dec("1299.99"));
+        assert_eq!(products[0].price.currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn content_attribute_price_is_machine_readable() {
+        let products = microdata(
+            r#"<div itemscope itemtype="https://schema.org/Product">
+                <span itemprop="name">Taladro</span>
+                <span itemprop="price" content="1299.99">1.299,99 €</span>
+            </div>"#,
+        );
+        assert_eq!(products[0].price.amount, dec("1299.99"));
+    }
+
+    #[test]
+    fn nested_scopes_do_not_leak_properties() {
+        let products = microdata(
+            r#"<div itemscope itemtype="https://schema.org/Product">
+                <div itemprop="brand" itemscope itemtype="https://schema.org/Brand">
+                    <span itemprop="name">Bosch</span>
+                </div>
+                <span itemprop="name">Taladro percutor</span>
+                <div itemprop="isRelatedTo" itemscope itemtype="https://schema.org/Product">
+                    <span itemprop="name">Brocas</span>
+                    <span itemprop="sku">BR-1</span>
+                </div>
+            </div>"#,
+        );
+        assert_eq!(products.len(), 1);
+        assert_eq!(products[0].name, "Taladro percutor");
+        assert_eq!(products[0].brand.as_deref(), Some("Bosch"));
+        assert_eq!(products[0].sku, None);
+    }
+
+    #[test]
+    fn item_list_entries_are_products() {
+        let products = microdata(
+            r#"<ol itemscope itemtype="https://schema.org/ItemList">
+                <li itemprop="itemListElement" itemscope itemtype="https://schema.org/ListItem">
+                    <meta itemprop="position" content="1">
+                    <div itemprop="item" itemscope itemtype="https://schema.org/Product">
+                        <a itemprop="url" href="/p/1"><span itemprop="name">Taladro</span></a>
+                        <span itemprop="price" content="10.50"></span>
+                    </div>
+                </li>
+                <li itemprop="itemListElement" itemscope itemtype="https://schema.org/Product">
+                    <span itemprop="name">Amoladora</span>
+                </li>
+            </ol>"#,
+        );
+        let names: Vec<&str> = products.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Taladro", "Amoladora"]);
+        assert_eq!(products[0].url, "https://tienda.example/p/1");
+        assert_eq!(products[0].price.amount, dec("10.50"));
+    }
+
+    #[test]
+    fn json_ld_product_with_offer() {
+        let products = json_ld(
+            r#"{
+                "@context": "https://schema.org",
+                "@type": "Product",
+                "name": "Taladro percutor",
+                "url": "/p/1",
+                "image": {"@type": "ImageObject", "url": "https://cdn.example/1.jpg"},
+                "gtin13": "7791234567890",
+                "sku": "TP-600",
+                "brand": {"@type": "Brand", "name": "Bosch"},
+                "offers": {"@type": "Offer", "price": "1299.99", "priceCurrency": "EUR"}
+            }"#,
+        );
+        assert_eq!(products.len(), 1);
+        let product = &products[0];
+        assert_eq!(product.name, "Taladro percutor");
+        assert_eq!(product.url, "https://tienda.example/p/1");
+        assert_eq!(product.image_url, "https://cdn.example/1.jpg");
+        // JSON-LD usa punto decimal aunque la tienda use coma
+        assert_eq!(product.price.amount, dec("1299.99"));
+        assert_eq!(product.price.currency.as_deref(), Some("EUR"));
+        assert_eq!(product.gtin.as_deref(), Some("7791234567890"));
+        assert_eq!(product.sku.as_deref(), Some("TP-600"));
+        assert_eq!(product.brand.as_deref(), Some("Bosch"));
+    }
+
+    #[test]
+    fn json_ld_graph_is_searched() {
+        let products = json_ld(
+            r#"{
+                "@context": "https://schema.org",
+                "@graph": [
+                    {"@type": "WebPage", "name": "Resultados"},
+                    {"@type": ["Product", "Thing"], "name": "Lijadora", "offers": {"price": 45}}
+                ]
+            }"#,
+        );
+        let names: Vec<&str> = products.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Lijadora"]);
+        assert_eq!(products[0].price.amount, dec("45"));
+        // Sin URL propia, el producto apunta a la página
+        assert_eq!(products[0].url, PAGE);
+    }
+
+    #[test]
+    fn json_ld_item_list_unwraps_list_items() {
+        let products = json_ld(
+            r#"{
+                "@type": "ItemList",
+                "itemListElement": [
+                    {"@type": "ListItem", "position": 1, "item": {"@type": "Product", "name": "Taladro", "url": "/p/1"}},
+                    {"@type": "Product", "name": "Lijadora", "url": "/p/2"},
+                    {"@type": "ListItem", "position": 3, "url": "/p/3"}
+                ]
+            }"#,
+        );
+        let names: Vec<&str> = products.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Taladro", "Lijadora"]);
+    }
+
+    #[test]
+    fn aggregate_offer_uses_the_low_price() {
+        let products = json_ld(
+            r#"{"@type": "Product", "name": "Taladro",
+                "offers": {"@type": "AggregateOffer", "lowPrice": "89.90", "highPrice": "120", "priceCurrency": "USD"}}"#,
+        );
+        assert_eq!(products[0].price.amount, dec("89.90"));
+        assert_eq!(products[0].price.currency.as_deref(), Some("USD"));
+    }
+
+    #[test]
+    fn cheapest_of_several_offers_wins() {
+        let products = json_ld(
+            r#"{"@type": "Product", "name": "Taladro", "offers": [
+                {"@type": "Offer", "price": "120", "priceCurrency": "USD"},
+                {"@type": "Offer", "price": "Consultar"},
+                {"@type": "Offer", "priceSpecification": {"price": 99.5, "priceCurrency": "USD"}}
+            ]}"#,
+        );
+        assert_eq!(products[0].price.amount, dec("99.5"));
+        assert_eq!(products[0].price.currency.as_deref(), Some("USD"));
+    }
+
+    #[test]
+    fn invalid_json_ld_block_is_skipped_and_json_ld_wins_over_microdata() {
+        let store = store();
+        let html = r#"<html><head>
+            <script type="application/ld+json">{ roto </script>
+            <script type="application/ld+json">{"@type": "Product", "name": "Desde JSON-LD"}</script>
+            </head><body>
+            <div itemscope itemtype="https://schema.org/Product"><span itemprop="name">Desde microdata</span></div>
+            </body></html>"#;
+        let products = StructuredDataExtractor::new(&store, PAGE).extract(&Html::parse_document(html));
+        let names: Vec<&str> = products.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Desde JSON-LD"]);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::models::{AlertCondition, AlertMatch, AlertRule, AlertTarget, Product, Watchlist};
+use crate::utils::{FileManager, PriceHistory};
+use eframe::egui;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TargetKind {
+    Query,
+    Url,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConditionKind {
+    PriceBelow,
+    PriceDrop,
+}
+
+pub struct AlertsTab {
+    watchlist: Watchlist,
+    file_manager: FileManager,
+    // Coincidencias de todas las búsquedas de la sesión, la más reciente primero
+    matches: Vec<AlertMatch>,
+    unseen_matches: usize,
+    rule_name: String,
+    target_kind: TargetKind,
+    query: String,
+    store: String,
+    url: String,
+    condition_kind: ConditionKind,
+    value: String,
+    form_error: Option<String>,
+}
+
+impl AlertsTab {
+    pub fn new() -> Self {
+        let file_manager = FileManager::new();
+        Self {
+            watchlist: file_manager.load_watchlist(),
+            file_manager,
+            matches: Vec::new(),
+            unseen_matches: 0,
+            rule_name: String::new(),
+            target_kind: TargetKind::Query,
+            query: String::new(),
+            store: String::new(),
+            url: String::new(),
+            condition_kind: ConditionKind::PriceBelow,
+            value: String::new(),
+            form_error: None,
+        }
+    }
+
+    /// Evalúa las reglas contra un lote nuevo de resultados y guarda las coincidencias
+    pub fn evaluate(&mut self, products: &[Product], history: Option<&PriceHistory>) -> usize {
+        let new_matches = self.watchlist.evaluate(products, history);
+        let count = new_matches.len();
+
+        self.unseen_matches += count;
+        self.matches.splice(0..0, new_matches);
+        count
+    }
+
+    /// Coincidencias que llegaron desde la última vez que se abrió el panel
+    pub fn unseen_matches(&self) -> usize {
+        self.unseen_matches
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.unseen_matches = 0;
+
+        ui.heading("🔔 Alertas de Precio");
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} coincidencias", self.matches.len()));
+            if ui.add_enabled(!self.matches.is_empty(), egui::Button::new("🗑️ Limpiar")).clicked() {
+                self.matches.clear();
+            }
+        });
+
+        egui::ScrollArea::vertical()
+            .id_source("alert_matches")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                if self.matches.is_empty() {
+                    ui.label("Ninguna regla se ha cumplido todavía.");
+                }
+                for alert in &self.matches {
+                    ui.horizontal(|ui| {
+                        ui.label(alert.matched_at.with_timezone(&chrono::Local).format("%H:%M").to_string());
+                        ui.label(alert.message());
+                        ui.hyperlink_to("Ver en tienda", &alert.product.url);
+                    });
+                }
+            });
+
+        ui.add_space(10.0);
+        ui.heading("Reglas");
+        ui.separator();
+
+        let mut changed = false;
+        let mut remove = None;
+        for (i, rule) in self.watchlist.rules.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                changed |= ui.checkbox(&mut rule.enabled, &rule.name).changed();
+                ui.label(rule.describe());
+                if ui.small_button("❌").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if self.watchlist.rules.is_empty() {
+            ui.label("No hay reglas. Añade una abajo.");
+        }
+        if let Some(i) = remove {
+            self.watchlist.rules.remove(i);
+            changed = true;
+        }
+
+        ui.add_space(10.0);
+        ui.collapsing("➕ Nueva regla", |ui| {
+            if self.show_rule_form(ui) {
+                changed = true;
+            }
+        });
+
+        if changed && let Err(e) = self.file_manager.save_watchlist(&self.watchlist) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Formulario de alta; devuelve true si se añadió una regla
+    fn show_rule_form(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            ui.label("Nombre:");
+            ui.text_edit_singleline(&mut self.rule_name);
+        });
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.target_kind, TargetKind::Query, "Búsqueda");
+            ui.radio_value(&mut self.target_kind, TargetKind::Url, "URL de producto");
+        });
+
+        match self.target_kind {
+            TargetKind::Query => {
+                ui.horizontal(|ui| {
+                    ui.label("Palabras:");
+                    ui.text_edit_singleline(&mut self.query);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Tienda (vacío = cualquiera):");
+                    ui.text_edit_singleline(&mut self.store);
+                });
+            }
+            TargetKind::Url => {
+                ui.horizontal(|ui| {
+                    ui.label("URL:");
+                    ui.text_edit_singleline(&mut self.url);
+                });
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.condition_kind, ConditionKind::PriceBelow, "Precio menor que");
+            ui.radio_value(&mut self.condition_kind, ConditionKind::PriceDrop, "Baja de precio (%)");
+            ui.add(egui::TextEdit::singleline(&mut self.value).desired_width(80.0));
+        });
+
+        let mut added = false;
+        if ui.button("Añadir regla").clicked() {
+            match self.build_rule() {
+                Ok(rule) => {
+                    self.watchlist.rules.push(rule);
+                    self.rule_name.clear();
+                    self.query.clear();
+                    self.store.clear();
+                    self.url.clear();
+                    self.value.clear();
+                    self.form_error = None;
+                    added = true;
+                }
+                Err(e) => self.form_error = Some(e),
+            }
+        }
+
+        if let Some(error) = &self.form_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        added
+    }
+
+    fn build_rule(&self) -> Result<AlertRule, String> {
+        let target = match self.target_kind {
+            TargetKind::Query if self.query.trim().is_empty() => return Err("Escribe las palabras a vigilar".to_string()),
+            TargetKind::Query => AlertTarget::Query {
+                query: self.query.trim().to_string(),
+                store: Some(self.store.trim().to_string()).filter(|s| !s.is_empty()),
+            },
+            TargetKind::Url if self.url.trim().is_empty() => return Err("Escribe la URL del producto".to_string()),
+            TargetKind::Url => AlertTarget::Url {
+                url: self.url.trim().to_string(),
+            },
+        };
+
+        let value = Decimal::from_str(&self.value.trim().replace(',', "."))
+            .ok()
+            .filter(|v| *v > Decimal::ZERO)
+            .ok_or("El valor debe ser un número mayor que cero")?;
+        let condition = match self.condition_kind {
+            ConditionKind::PriceBelow => AlertCondition::PriceBelow { amount: value },
+            ConditionKind::PriceDrop => AlertCondition::PriceDrop { percent: value },
+        };
+
+        let mut rule = AlertRule {
+            name: self.rule_name.trim().to_string(),
+            enabled: true,
+            target,
+            condition,
+        };
+        if rule.name.is_empty() {
+            rule.name = rule.describe();
+        }
+        Ok(rule)
+    }
+}
+
+impl Default for AlertsTab {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,253 @@
+use crate::models::StoreConfig;
+use crate::scraping::{HttpSettings, WebScraper};
+use eframe::egui;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+const WORKERS: usize = 4;
+pub const THUMBNAIL_SIZE: f32 = 64.0;
+pub const PREVIEW_SIZE: f32 = 320.0;
+// Imágenes en memoria antes de descartar las que llevan más tiempo sin mostrarse
+const MAX_IMAGES: usize = 500;
+
+struct ImageJob {
+    url: String,
+    // Proxy y límite de velocidad de la tienda del producto
+    settings: HttpSettings,
+    ctx: egui::Context,
+}
+
+/// Imagen decodificada y reducida en un hilo de trabajo
+struct DecodedImage {
+    thumbnail: egui::ColorImage,
+    preview: egui::ColorImage,
+}
+
+enum ImageState {
+    Loading,
+    Ready {
+        thumbnail: egui::TextureHandle,
+        preview: egui::TextureHandle,
+    },
+    Failed,
+}
+
+struct CachedImage {
+    state: ImageState,
+    // Valor de `ImageLoader::clock` la última vez que se mostró
+    last_used: u64,
+}
+
+/// Descarga y decodifica imágenes de productos en segundo plano y las guarda como texturas
+pub struct ImageLoader {
+    scraper: Arc<WebScraper>,
+    jobs: Sender<ImageJob>,
+    results: Receiver<(String, Result<DecodedImage, String>)>,
+    images: HashMap<String, CachedImage>,
+    // Cuenta cada vez que se muestra una imagen, para saber cuál se usó hace más
+    clock: u64,
+}
+
+impl ImageLoader {
+    pub fn new(scraper: Arc<WebScraper>) -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<ImageJob>();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        for _ in 0..WORKERS {
+            let scraper = Arc::clone(&scraper);
+            let job_receiver = Arc::clone(&job_receiver);
+            let result_sender = result_sender.clone();
+
+            std::thread::spawn(move || {
+                loop {
+                    // El lock se suelta antes de descargar para que los demás hilos sigan
+                    let job = job_receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    let Ok(job) = job else {
+                        break;
+                    };
+
+                    let result = scraper
+                        .fetch_image(&job.url, &job.settings)
+                        .map_err(|e| e.to_string())
+                        .and_then(|bytes| decode_image(&bytes));
+                    if result_sender.send((job.url, result)).is_err() {
+                        break;
+                    }
+                    job.ctx.request_repaint();
+                }
+            });
+        }
+
+        Self {
+            scraper,
+            jobs,
+            results,
+            images: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Convierte en texturas las imágenes que terminaron; llamar una vez por frame
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        for (url, result) in self.results.try_iter() {
+            let state = match result {
+                Ok(decoded) => ImageState::Ready {
+                    thumbnail: ctx.load_texture(format!("thumb:{}", url), decoded.thumbnail, Default::default()),
+                    preview: ctx.load_texture(format!("preview:{}", url), decoded.preview, Default::default()),
+                },
+                Err(e) => {
+                    eprintln!("Error al cargar la imagen {}: {}", url, e);
+                    ImageState::Failed
+                }
+            };
+            match self.images.get_mut(&url) {
+                Some(image) => image.state = state,
+                None => {
+                    let last_used = self.clock;
+                    self.images.insert(url, CachedImage { state, last_used });
+                }
+            }
+        }
+    }
+
+    /// Muestra la miniatura de la URL o un marcador mientras carga o si falló. La imagen
+    /// se descarga con los ajustes HTTP de la tienda, si se conoce.
+    pub fn thumbnail(&mut self, ui: &mut egui::Ui, url: &str, store: Option<&StoreConfig>) {
+        self.show(ui, url, store, THUMBNAIL_SIZE, false);
+    }
+
+    /// Muestra la imagen grande para el panel de detalles
+    pub fn preview(&mut self, ui: &mut egui::Ui, url: &str, store: Option<&StoreConfig>) {
+        self.show(ui, url, store, PREVIEW_SIZE, true);
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, url: &str, store: Option<&StoreConfig>, size: f32, large: bool) {
+        if url.trim().is_empty() {
+            placeholder(ui, size, "🖼");
+            return;
+        }
+
+        match self.state(ui.ctx(), url, store) {
+            ImageState::Loading => placeholder(ui, size, "⏳"),
+            ImageState::Failed => placeholder(ui, size, "🚫"),
+            ImageState::Ready { thumbnail, preview } => {
+                let texture = if large { preview } else { thumbnail };
+                ui.add(egui::Image::new(texture).max_size(egui::vec2(size, size)));
+            }
+        }
+    }
+
+    /// Estado de la imagen, pidiendo su descarga la primera vez que se ve
+    fn state(&mut self, ctx: &egui::Context, url: &str, store: Option<&StoreConfig>) -> &ImageState {
+        self.clock += 1;
+        if let Some(image) = self.images.get_mut(url) {
+            image.last_used = self.clock;
+        } else {
+            if self.images.len() >= MAX_IMAGES {
+                self.evict_least_recently_used();
+            }
+
+            let job = ImageJob {
+                url: url.to_string(),
+                settings: self.scraper.image_settings(store),
+                ctx: ctx.clone(),
+            };
+            let state = if self.jobs.send(job).is_ok() {
+                ImageState::Loading
+            } else {
+                ImageState::Failed
+            };
+            self.images.insert(
+                url.to_string(),
+                CachedImage {
+                    state,
+                    last_used: self.clock,
+                },
+            );
+        }
+
+        &self.images[url].state
+    }
+
+    /// Descarta la imagen que lleva más tiempo sin mostrarse; las que están cargando
+    /// se conservan porque su resultado aún debe llegar
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self
+            .images
+            .iter()
+            .filter(|(_, image)| !matches!(image.state, ImageState::Loading))
+            .min_by_key(|(_, image)| image.last_used)
+            .map(|(url, _)| url.clone());
+        if let Some(url) = oldest {
+            self.images.remove(&url);
+        }
+    }
+}
+
+fn decode_image(bytes: &[u8]) -> Result<DecodedImage, String> {
+    let image = image::load_from_memory(bytes).map_err(|e| format!("no se pudo decodificar: {}", e))?;
+
+    Ok(DecodedImage {
+        thumbnail: to_color_image(&image, THUMBNAIL_SIZE as u32),
+        preview: to_color_image(&image, PREVIEW_SIZE as u32),
+    })
+}
+
+/// Reduce la imagen para que quepa en `max_side` (nunca la agranda)
+fn to_color_image(image: &image::DynamicImage, max_side: u32) -> egui::ColorImage {
+    let resized = if image.width() > max_side || image.height() > max_side {
+        image.thumbnail(max_side, max_side)
+    } else {
+        image.clone()
+    };
+
+    let rgba = resized.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw())
+}
+
+fn placeholder(ui: &mut egui::Ui, size: f32, icon: &str) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let visuals = ui.visuals();
+    ui.painter().rect_filled(rect, 4.0, visuals.faint_bg_color);
+    ui.painter().text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        icon,
+        egui::FontId::proportional(size / 3.0),
+        visuals.weak_text_color(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(state: ImageState, last_used: u64) -> CachedImage {
+        CachedImage { state, last_used }
+    }
+
+    #[test]
+    fn full_cache_evicts_the_least_recently_used_image() {
+        let ctx = egui::Context::default();
+        let mut loader = ImageLoader::new(Arc::new(WebScraper::for_tests(0)));
+        loader.images.insert("cargando".to_string(), cached(ImageState::Loading, 0));
+        for i in 1..MAX_IMAGES {
+            loader.images.insert(format!("imagen-{}", i), cached(ImageState::Failed, i as u64));
+        }
+        loader.clock = MAX_IMAGES as u64;
+
+        // Mostrarla de nuevo la convierte en la más reciente
+        loader.state(&ctx, "imagen-1", None);
+        loader.state(&ctx, "nueva", None);
+
+        assert_eq!(loader.images.len(), MAX_IMAGES);
+        assert!(loader.images.contains_key("cargando"));
+        assert!(loader.images.contains_key("imagen-1"));
+        assert!(!loader.images.contains_key("imagen-2"));
+        assert!(loader.images.contains_key("imagen-3"));
+        assert!(loader.images.contains_key("nueva"));
+    }
+}
@@ -1,7 +1,13 @@
 pub mod search_tab;
 pub mod store_tab;
 pub mod results_tab;
+pub mod alerts_tab;
+pub mod schedules_tab;
+pub mod image_loader;
+pub mod store_bundles;
 
 pub use search_tab::SearchTab;
 pub use store_tab::StoreTab;
-pub use results_tab::ResultsTab;
\ No newline at end of file
+pub use results_tab::ResultsTab;
+pub use alerts_tab::AlertsTab;
+pub use schedules_tab::SchedulesTab;
\ No newline at end of file
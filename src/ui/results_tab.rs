@@ -1,6 +1,94 @@
-use crate::models::Product;
-use crate::utils::FileManager;
+use crate::models::{group_products, ExtraValue, Product, ProductGroup, StoreConfig, StoreManager};
+use crate::scraping::WebScraper;
+use crate::ui::image_loader::{ImageLoader, THUMBNAIL_SIZE};
+use crate::utils::{CsvColumn, CsvEncoding, CsvOptions, FileManager};
 use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+enum SortColumn {
+    Name,
+    Price,
+    Store,
+    Description,
+    // Campo personalizado por nombre
+    Extra(String),
+}
+
+/// Forma de presentar los resultados
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ResultsView {
+    Table,
+    // Mismo producto en varias tiendas
+    Groups,
+    // Resultados de una búsqueda por lotes, por consulta
+    ByQuery,
+}
+
+/// Filtros de la tabla de resultados
+#[derive(Debug, Default)]
+struct ResultsFilter {
+    text: String,
+    min_price: String,
+    max_price: String,
+    // Se guardan las tiendas ocultas para que las nuevas aparezcan por defecto
+    hidden_stores: HashSet<String>,
+    // Filtro por campo personalizado; vacío no filtra
+    extra: BTreeMap<String, String>,
+}
+
+impl ResultsFilter {
+    fn matches(&self, product: &Product) -> bool {
+        if self.hidden_stores.contains(&product.store_name) {
+            return false;
+        }
+
+        let text = self.text.trim().to_lowercase();
+        if !text.is_empty() {
+            let in_name = product.name.to_lowercase().contains(&text);
+            let in_description = product
+                .description
+                .as_ref()
+                .is_some_and(|d| d.to_lowercase().contains(&text));
+            if !in_name && !in_description {
+                return false;
+            }
+        }
+
+        for (key, filter) in self.extra.iter().filter(|(_, filter)| !filter.trim().is_empty()) {
+            if !product.extra.get(key).is_some_and(|value| matches_extra(value, filter)) {
+                return false;
+            }
+        }
+
+        // Con un límite de precio, los productos sin monto quedan fuera
+        let min = parse_bound(&self.min_price);
+        let max = parse_bound(&self.max_price);
+        if min.is_some() || max.is_some() {
+            let Some(amount) = product.price.amount else {
+                return false;
+            };
+            if min.is_some_and(|min| amount < min) || max.is_some_and(|max| amount > max) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn is_active(&self) -> bool {
+        !self.text.trim().is_empty()
+            || parse_bound(&self.min_price).is_some()
+            || parse_bound(&self.max_price).is_some()
+            || !self.hidden_stores.is_empty()
+            || self.extra.values().any(|filter| !filter.trim().is_empty())
+    }
+}
 
 pub struct ResultsTab {
     selected_product: Option<usize>,
@@ -8,20 +96,54 @@ pub struct ResultsTab {
     show_save_message: bool,
     save_message: String,
     save_message_time: f32,
+    // Sin columna se respeta el orden en que se obtuvieron los productos
+    sort_column: Option<SortColumn>,
+    sort_descending: bool,
+    filter: ResultsFilter,
+    images: ImageLoader,
+    view: ResultsView,
+    show_export: bool,
+    export_path: String,
+    export_visible_only: bool,
+    csv_options: CsvOptions,
+    // Grupos calculados para la última lista de productos visibles
+    groups: Option<(Vec<usize>, Vec<ProductGroup>)>,
 }
 
 impl ResultsTab {
-    pub fn new() -> Self {
+    pub fn new(scraper: Arc<WebScraper>) -> Self {
+        let file_manager = FileManager::new();
+        let csv_options = file_manager.load_app_config().csv_export;
+
         Self {
             selected_product: None,
-            file_manager: FileManager::new(),
+            file_manager,
             show_save_message: false,
             save_message: String::new(),
             save_message_time: 0.0,
+            sort_column: None,
+            sort_descending: false,
+            filter: ResultsFilter::default(),
+            images: ImageLoader::new(scraper),
+            view: ResultsView::Table,
+            show_export: false,
+            export_path: "resultados.csv".to_string(),
+            export_visible_only: true,
+            csv_options,
+            groups: None,
         }
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, products: Option<&Vec<Product>>) {
+    /// Olvida la selección y los grupos al llegar resultados nuevos
+    pub fn reset(&mut self) {
+        self.selected_product = None;
+        self.groups = None;
+    }
+
+    /// `stores` sirve para descargar cada imagen con los ajustes HTTP de su tienda
+    pub fn show(&mut self, ui: &mut egui::Ui, products: Option<&Vec<Product>>, stores: &StoreManager) {
+        self.images.poll(ui.ctx());
+
         ui.heading("📊 Resultados de Búsqueda");
         ui.separator();
 
@@ -47,6 +169,10 @@ impl ResultsTab {
                     }
                 }
 
+                if ui.button("📄 Exportar CSV").clicked() {
+                    self.show_export = true;
+                }
+
                 if self.show_save_message {
                     ui.label(&self.save_message);
                     self.save_message_time -= ui.ctx().input(|i| i.unstable_dt);
@@ -56,76 +182,622 @@ impl ResultsTab {
                 }
             });
 
-            ui.add_space(10.0);
+            let extra_keys: Vec<String> = products
+                .iter()
+                .flat_map(|p| p.extra.keys())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .cloned()
+                .collect();
 
-            // Panel dividido: lista de productos a la izquierda, detalles a la derecha
-            egui::SidePanel::left("products_list")
+            ui.add_space(5.0);
+            self.show_filters(ui, products, &extra_keys);
+
+            let visible = self.visible_products(products);
+            ui.label(format!("Mostrando {} de {} productos", visible.len(), products.len()));
+
+            if self.show_export {
+                self.show_export_window(ui.ctx(), products, &visible);
+            }
+            ui.add_space(5.0);
+
+            // Panel de detalles del producto a la derecha, tabla en el resto
+            egui::SidePanel::right("product_details")
                 .resizable(true)
-                .default_width(300.0)
+                .default_width(340.0)
                 .show_inside(ui, |ui| {
-                    ui.heading("Productos");
-                    ui.separator();
-
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        for (i, product) in products.iter().enumerate() {
-                            let is_selected = self.selected_product == Some(i);
-                            let response = ui.selectable_label(
-                                is_selected,
-                                format!("{}\n{}", product.name, product.price),
+                    match self.selected_product.and_then(|i| products.get(i)) {
+                        Some(product) => self.show_details(ui, product, store_of(stores, product)),
+                        None => {
+                            ui.label("Selecciona un producto para ver detalles");
+                        }
+                    }
+                });
+
+            match self.view {
+                ResultsView::Table => self.show_table(ui, products, &visible, &extra_keys, stores),
+                ResultsView::Groups => self.show_groups(ui, products, visible),
+                ResultsView::ByQuery => self.show_by_query(ui, products, &visible),
+            }
+        } else {
+            ui.label("Realiza una búsqueda para ver resultados.");
+        }
+    }
+
+    fn show_filters(&mut self, ui: &mut egui::Ui, products: &[Product], extra_keys: &[String]) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Filtrar:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter.text)
+                    .hint_text("nombre o descripción")
+                    .desired_width(180.0),
+            );
+
+            ui.label("Precio:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter.min_price)
+                    .hint_text("mín")
+                    .desired_width(70.0),
+            );
+            ui.label("–");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter.max_price)
+                    .hint_text("máx")
+                    .desired_width(70.0),
+            );
+
+            let stores: BTreeSet<&str> = products.iter().map(|p| p.store_name.as_str()).collect();
+            let shown_stores = stores
+                .iter()
+                .filter(|store| !self.filter.hidden_stores.contains(**store))
+                .count();
+            ui.menu_button(format!("Tiendas ({}/{})", shown_stores, stores.len()), |ui| {
+                for store in &stores {
+                    let mut shown = !self.filter.hidden_stores.contains(*store);
+                    if ui.checkbox(&mut shown, *store).changed() {
+                        if shown {
+                            self.filter.hidden_stores.remove(*store);
+                        } else {
+                            self.filter.hidden_stores.insert(store.to_string());
+                        }
+                    }
+                }
+            });
+
+            if !extra_keys.is_empty() {
+                let active = self.filter.extra.values().filter(|f| !f.trim().is_empty()).count();
+                ui.menu_button(format!("Campos ({})", active), |ui| {
+                    egui::Grid::new("extra_filters").show(ui, |ui| {
+                        for key in extra_keys {
+                            ui.label(key);
+                            ui.add(
+                                egui::TextEdit::singleline(self.filter.extra.entry(key.clone()).or_default())
+                                    .hint_text("texto o >= 4")
+                                    .desired_width(100.0),
                             );
+                            ui.end_row();
+                        }
+                    });
+                });
+            }
 
-                            if response.clicked() {
-                                self.selected_product = Some(i);
-                            }
+            if ui
+                .add_enabled(self.filter.is_active(), egui::Button::new("Limpiar filtros"))
+                .clicked()
+            {
+                self.filter = ResultsFilter::default();
+            }
+
+            ui.separator();
+            ui.label("Vista:");
+            ui.selectable_value(&mut self.view, ResultsView::Table, "Tabla");
+            ui.selectable_value(&mut self.view, ResultsView::Groups, "Productos iguales");
+            if products.iter().any(|p| p.query.is_some()) {
+                ui.selectable_value(&mut self.view, ResultsView::ByQuery, "Por consulta");
+            } else if self.view == ResultsView::ByQuery {
+                self.view = ResultsView::Table;
+            }
+        });
+    }
+
+    /// Ventana de exportación: ruta, formato y columnas
+    fn show_export_window(&mut self, ctx: &egui::Context, products: &[Product], visible: &[usize]) {
+        let mut open = self.show_export;
+        let mut exported = false;
+
+        egui::Window::new("Exportar CSV")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Guardar en:");
+                    ui.text_edit_singleline(&mut self.export_path);
+                });
+
+                let options = &mut self.csv_options;
+                egui::ComboBox::from_label("Delimitador")
+                    .selected_text(delimiter_label(options.delimiter))
+                    .show_ui(ui, |ui| {
+                        for delimiter in [',', ';', '\t', '|'] {
+                            ui.selectable_value(&mut options.delimiter, delimiter, delimiter_label(delimiter));
                         }
                     });
+                egui::ComboBox::from_label("Codificación")
+                    .selected_text(encoding_label(options.encoding))
+                    .show_ui(ui, |ui| {
+                        for encoding in [CsvEncoding::Utf8, CsvEncoding::Utf8Bom, CsvEncoding::Windows1252] {
+                            ui.selectable_value(&mut options.encoding, encoding, encoding_label(encoding));
+                        }
+                    });
+
+                ui.label("Columnas:");
+                ui.horizontal_wrapped(|ui| {
+                    // Se conserva siempre el orden de CsvColumn::ALL
+                    let mut selected: Vec<CsvColumn> = Vec::new();
+                    for column in CsvColumn::ALL {
+                        let mut checked = options.columns.contains(&column);
+                        ui.checkbox(&mut checked, column.header());
+                        if checked {
+                            selected.push(column);
+                        }
+                    }
+                    options.columns = selected;
                 });
+                ui.checkbox(&mut options.extra_fields, "Campos personalizados de las tiendas");
 
-            // Panel de detalles del producto
-            if let Some(selected_idx) = self.selected_product {
-                if let Some(product) = products.get(selected_idx) {
-                    ui.vertical(|ui| {
-                        ui.heading(&product.name);
-                        ui.label(format!("Precio: {}", product.price));
-                        ui.label(format!("Tienda: {}", product.store_name));
-                        
-                        if let Some(desc) = &product.description {
-                            ui.label(format!("Descripción: {}", desc));
+                ui.checkbox(
+                    &mut self.export_visible_only,
+                    format!("Sólo los productos visibles ({} de {})", visible.len(), products.len()),
+                );
+
+                ui.separator();
+                let can_export = !self.export_path.trim().is_empty() && !self.csv_options.columns.is_empty();
+                if ui.add_enabled(can_export, egui::Button::new("Exportar")).clicked() {
+                    let selection: Vec<Product> = if self.export_visible_only {
+                        visible.iter().map(|&i| products[i].clone()).collect()
+                    } else {
+                        products.to_vec()
+                    };
+                    let path = self.export_path.trim();
+
+                    match self.file_manager.export_to_csv(&selection, path, &self.csv_options) {
+                        Ok(_) => {
+                            self.save_message = format!("✅ {} productos exportados a {}", selection.len(), path);
+                            self.save_message_time = 3.0;
+                            exported = true;
                         }
+                        Err(e) => {
+                            self.save_message = format!("❌ {}", e);
+                            self.save_message_time = 5.0;
+                        }
+                    }
+                    self.show_save_message = true;
+                }
+            });
 
-                        ui.horizontal(|ui| {
-                            if ui.button("🔗 Abrir enlace").clicked() {
-                                if let Err(e) = open::that(&product.url) {
-                                    eprintln!("Error al abrir URL: {}", e);
-                                }
-                            }
+        if exported {
+            // Recuerda las opciones para la próxima exportación y para la CLI
+            let mut config = self.file_manager.load_app_config();
+            config.csv_export = self.csv_options.clone();
+            if let Err(e) = self.file_manager.save_app_config(&config) {
+                eprintln!("{}", e);
+            }
+            open = false;
+        }
+        self.show_export = open;
+    }
+
+    /// Índices de los productos que pasan los filtros, en el orden elegido
+    fn visible_products(&self, products: &[Product]) -> Vec<usize> {
+        let mut visible: Vec<usize> = (0..products.len())
+            .filter(|&i| self.filter.matches(&products[i]))
+            .collect();
+
+        if let Some(column) = &self.sort_column {
+            visible.sort_by(|&a, &b| compare_by(column, &products[a], &products[b], self.sort_descending));
+        }
+
+        visible
+    }
+
+    fn show_table(
+        &mut self,
+        ui: &mut egui::Ui,
+        products: &[Product],
+        visible: &[usize],
+        extra_keys: &[String],
+        stores: &StoreManager,
+    ) {
+        let row_height = THUMBNAIL_SIZE + 8.0;
+        // La última columna ocupa el espacio sobrante
+        let description_column = if extra_keys.is_empty() {
+            Column::remainder().clip(true)
+        } else {
+            Column::initial(220.0).at_least(60.0).clip(true)
+        };
 
-                            ui.hyperlink_to("Ver en tienda", &product.url);
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .sense(egui::Sense::click())
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::exact(THUMBNAIL_SIZE))
+            .column(Column::initial(260.0).at_least(100.0).clip(true))
+            .column(Column::initial(110.0).at_least(60.0))
+            .column(Column::initial(110.0).at_least(60.0).clip(true))
+            .column(description_column)
+            .columns(Column::initial(100.0).at_least(50.0).clip(true), extra_keys.len())
+            .header(22.0, |mut header| {
+                header.col(|_| {});
+                for (column, label) in [
+                    (SortColumn::Name, "Nombre"),
+                    (SortColumn::Price, "Precio"),
+                    (SortColumn::Store, "Tienda"),
+                    (SortColumn::Description, "Descripción"),
+                ] {
+                    header.col(|ui| self.sort_header(ui, column, label));
+                }
+                for key in extra_keys {
+                    header.col(|ui| self.sort_header(ui, SortColumn::Extra(key.clone()), key));
+                }
+            })
+            .body(|body| {
+                body.rows(row_height, visible.len(), |mut row| {
+                    let index = visible[row.index()];
+                    let product = &products[index];
+                    row.set_selected(self.selected_product == Some(index));
+
+                    row.col(|ui| self.images.thumbnail(ui, &product.image_url, store_of(stores, product)));
+                    row.col(|ui| {
+                        ui.add(egui::Label::new(&product.name).truncate(true));
+                    });
+                    row.col(|ui| {
+                        ui.label(product.price.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(&product.store_name);
+                    });
+                    row.col(|ui| {
+                        ui.add(egui::Label::new(product.description.as_deref().unwrap_or("")).truncate(true));
+                    });
+                    for key in extra_keys {
+                        row.col(|ui| {
+                            if let Some(value) = product.extra.get(key) {
+                                ui.add(egui::Label::new(value.to_string()).truncate(true));
+                            }
                         });
+                    }
 
-                        ui.add_space(10.0);
-                        
-                        // Mostrar imagen si está disponible
-                        if !product.image_url.is_empty() {
-                            ui.label("Imagen del producto:");
-                            ui.hyperlink_to("Ver imagen", &product.image_url);
-                            // Nota: Para mostrar la imagen directamente, necesitarías
-                            // implementar carga de imágenes con egui, lo cual requiere
-                            // funcionalidades adicionales
-                        }
+                    if row.response().clicked() {
+                        self.selected_product = Some(index);
+                    }
+                });
+            });
+    }
+
+    /// Vista agrupada: cada grupo con su oferta más barata resaltada y la diferencia de precios
+    fn show_groups(&mut self, ui: &mut egui::Ui, products: &[Product], visible: Vec<usize>) {
+        if self.groups.as_ref().is_none_or(|(indices, _)| *indices != visible) {
+            let mut groups = group_products(products, &visible);
+            groups.sort_by(|a, b| match (a.cheapest, b.cheapest) {
+                (Some(a), Some(b)) => products[a].price.cmp_amount(&products[b].price),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            });
+            self.groups = Some((visible, groups));
+        }
+        let Some((_, groups)) = &self.groups else {
+            return;
+        };
+
+        ui.label(format!("{} grupos", groups.len()));
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for group in groups {
+                let first = &products[group.members[0]];
+                let title = match group.price_spread(products) {
+                    Some((min, max)) if group.members.len() > 1 && max > min && !min.is_zero() => format!(
+                        "{} — {} ofertas en {} tiendas, de {} a {} (+{}%)",
+                        first.name,
+                        group.members.len(),
+                        group.store_count(products),
+                        min,
+                        max,
+                        ((max - min) / min * Decimal::ONE_HUNDRED).round_dp(1)
+                    ),
+                    _ if group.members.len() > 1 => format!(
+                        "{} — {} ofertas en {} tiendas",
+                        first.name,
+                        group.members.len(),
+                        group.store_count(products)
+                    ),
+                    _ => format!("{} — {} en {}", first.name, first.price, first.store_name),
+                };
+
+                egui::CollapsingHeader::new(title)
+                    .id_source(("product_group", group.members[0]))
+                    .show(ui, |ui| {
+                        egui::Grid::new(("group_members", group.members[0]))
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for &index in &group.members {
+                                    let product = &products[index];
+                                    if group.cheapest == Some(index) {
+                                        ui.colored_label(egui::Color32::GREEN, format!("⭐ {}", product.price));
+                                    } else {
+                                        ui.label(product.price.to_string());
+                                    }
+                                    ui.label(&product.store_name);
+                                    if ui
+                                        .selectable_label(self.selected_product == Some(index), &product.name)
+                                        .clicked()
+                                    {
+                                        self.selected_product = Some(index);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
                     });
-                }
+            }
+        });
+    }
+
+    /// Vista por consulta: una sección por cada consulta del lote, en el orden en que se
+    /// buscaron, con la oferta más barata en el título
+    fn show_by_query(&mut self, ui: &mut egui::Ui, products: &[Product], visible: &[usize]) {
+        let mut sections: Vec<(&str, Vec<usize>)> = Vec::new();
+        for &index in visible {
+            let query = products[index].query.as_deref().unwrap_or("(sin consulta)");
+            match sections.iter_mut().find(|(q, _)| *q == query) {
+                Some((_, members)) => members.push(index),
+                None => sections.push((query, vec![index])),
+            }
+        }
+
+        ui.label(format!("{} consultas", sections.len()));
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (query, members) in &sections {
+                let cheapest = members
+                    .iter()
+                    .copied()
+                    .filter(|&i| products[i].price.amount.is_some())
+                    .min_by(|&a, &b| products[a].price.cmp_amount(&products[b].price));
+                let title = match cheapest {
+                    Some(i) => format!(
+                        "{} — {} productos, desde {} en {}",
+                        query,
+                        members.len(),
+                        products[i].price,
+                        products[i].store_name
+                    ),
+                    None => format!("{} — {} productos", query, members.len()),
+                };
+
+                egui::CollapsingHeader::new(title)
+                    .id_source(("query_section", *query))
+                    .show(ui, |ui| {
+                        egui::Grid::new(("query_members", *query))
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for &index in members {
+                                    let product = &products[index];
+                                    if cheapest == Some(index) {
+                                        ui.colored_label(egui::Color32::GREEN, format!("⭐ {}", product.price));
+                                    } else {
+                                        ui.label(product.price.to_string());
+                                    }
+                                    ui.label(&product.store_name);
+                                    if ui
+                                        .selectable_label(self.selected_product == Some(index), &product.name)
+                                        .clicked()
+                                    {
+                                        self.selected_product = Some(index);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+        });
+    }
+
+    /// Cabecera que ordena por la columna; un segundo clic invierte el orden
+    fn sort_header(&mut self, ui: &mut egui::Ui, column: SortColumn, label: &str) {
+        let active = self.sort_column.as_ref() == Some(&column);
+        let text = match (active, self.sort_descending) {
+            (true, false) => format!("{} ⬆", label),
+            (true, true) => format!("{} ⬇", label),
+            (false, _) => label.to_string(),
+        };
+
+        if ui.selectable_label(active, text).clicked() {
+            if active {
+                self.sort_descending = !self.sort_descending;
             } else {
-                ui.label("Selecciona un producto para ver detalles");
+                self.sort_column = Some(column);
+                self.sort_descending = false;
             }
-        } else {
-            ui.label("Realiza una búsqueda para ver resultados.");
         }
     }
+
+    fn show_details(&mut self, ui: &mut egui::Ui, product: &Product, store: Option<&StoreConfig>) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.heading(&product.name);
+            ui.label(format!("Precio: {}", product.price));
+            if product.price.amount.is_some() {
+                ui.label(format!("Precio interpretado: {}", product.price.formatted()));
+            }
+            ui.label(format!("Tienda: {}", product.store_name));
+            if let Some(query) = &product.query {
+                ui.label(format!("Consulta: {}", query));
+            }
+
+            if let Some(desc) = &product.description {
+                ui.label(format!("Descripción: {}", desc));
+            }
+
+            if !product.extra.is_empty() {
+                egui::Grid::new("product_extra").num_columns(2).show(ui, |ui| {
+                    for (key, value) in &product.extra {
+                        ui.label(format!("{}:", key));
+                        ui.label(value.to_string());
+                        ui.end_row();
+                    }
+                });
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("🔗 Abrir enlace").clicked()
+                    && let Err(e) = open::that(&product.url)
+                {
+                    eprintln!("Error al abrir URL: {}", e);
+                }
+
+                ui.hyperlink_to("Ver en tienda", &product.url);
+            });
+
+            ui.add_space(10.0);
+
+            self.images.preview(ui, &product.image_url, store);
+            if !product.image_url.is_empty() {
+                ui.hyperlink_to("Ver imagen original", &product.image_url);
+            }
+        });
+    }
 }
 
 impl Default for ResultsTab {
     fn default() -> Self {
-        Self::new()
+        Self::new(Arc::new(WebScraper::new()))
     }
-}
\ No newline at end of file
+}
+
+/// Tienda de la que salió el producto, si sigue configurada
+fn store_of<'a>(stores: &'a StoreManager, product: &Product) -> Option<&'a StoreConfig> {
+    stores.find_by_name(&product.store_name).map(|index| &stores.stores[index])
+}
+
+/// Los productos sin valor en la columna quedan al final en ambos sentidos
+fn compare_by(column: &SortColumn, a: &Product, b: &Product, descending: bool) -> Ordering {
+    let lowercase = |text: &str| Some(text.to_lowercase());
+    match column {
+        SortColumn::Name => missing_last(lowercase(&a.name), lowercase(&b.name), descending, |a, b| a.cmp(&b)),
+        SortColumn::Price => missing_last(a.price.amount, b.price.amount, descending, |a, b| a.cmp(&b)),
+        SortColumn::Store => missing_last(
+            lowercase(&a.store_name),
+            lowercase(&b.store_name),
+            descending,
+            |a, b| a.cmp(&b),
+        ),
+        SortColumn::Description => missing_last(
+            a.description.as_deref().and_then(lowercase),
+            b.description.as_deref().and_then(lowercase),
+            descending,
+            |a, b| a.cmp(&b),
+        ),
+        SortColumn::Extra(key) => missing_last(a.extra.get(key), b.extra.get(key), descending, |a, b| a.cmp_value(b)),
+    }
+}
+
+/// Sólo se invierte la comparación entre valores presentes
+fn missing_last<T>(a: Option<T>, b: Option<T>, descending: bool, compare: impl FnOnce(T, T) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => compare(a, b).reverse(),
+        (Some(a), Some(b)) => compare(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Filtro de un campo personalizado: "> 4", ">= 4", "< 10", "<= 10" o "= 3" comparan
+/// números; cualquier otro texto se busca dentro del valor
+fn matches_extra(value: &ExtraValue, filter: &str) -> bool {
+    let filter = filter.trim();
+    let comparison = [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|op| filter.strip_prefix(op).map(|rest| (op, rest)));
+
+    if let Some((op, rest)) = comparison
+        && let (Some(number), Some(bound)) = (value.as_number(), parse_bound(rest))
+    {
+        return match op {
+            ">=" => number >= bound,
+            "<=" => number <= bound,
+            ">" => number > bound,
+            "<" => number < bound,
+            _ => number == bound,
+        };
+    }
+
+    value.to_string().to_lowercase().contains(&filter.to_lowercase())
+}
+
+fn delimiter_label(delimiter: char) -> &'static str {
+    match delimiter {
+        ',' => "Coma (,)",
+        ';' => "Punto y coma (;)",
+        '\t' => "Tabulador",
+        '|' => "Barra (|)",
+        _ => "Otro",
+    }
+}
+
+fn encoding_label(encoding: CsvEncoding) -> &'static str {
+    match encoding {
+        CsvEncoding::Utf8 => "UTF-8",
+        CsvEncoding::Utf8Bom => "UTF-8 con BOM (Excel)",
+        CsvEncoding::Windows1252 => "Windows-1252",
+    }
+}
+
+/// Límite de precio escrito por el usuario; acepta coma decimal
+fn parse_bound(text: &str) -> Option<Decimal> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Decimal::from_str(&text.replace(',', ".")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Price, PriceLocale};
+
+    fn product(name: &str, price: &str) -> Product {
+        Product::new(
+            name.to_string(),
+            Price::parse(price, &PriceLocale::default()),
+            String::new(),
+            String::new(),
+            "Tienda".to_string(),
+        )
+    }
+
+    fn sorted_names(column: SortColumn, descending: bool) -> Vec<String> {
+        let mut products = vec![product("b", "Consultar"), product("a", "10"), product("c", "30")];
+        products[1].extra.insert("Stock".to_string(), ExtraValue::Number(5.into()));
+        products[2].extra.insert("Stock".to_string(), ExtraValue::Number(2.into()));
+        products.sort_by(|a, b| compare_by(&column, a, b, descending));
+        products.into_iter().map(|p| p.name).collect()
+    }
+
+    #[test]
+    fn missing_price_goes_last_in_both_directions() {
+        assert_eq!(sorted_names(SortColumn::Price, false), ["a", "c", "b"]);
+        assert_eq!(sorted_names(SortColumn::Price, true), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn missing_extra_goes_last_in_both_directions() {
+        let stock = || SortColumn::Extra("Stock".to_string());
+        assert_eq!(sorted_names(stock(), false), ["c", "a", "b"]);
+        assert_eq!(sorted_names(stock(), true), ["a", "c", "b"]);
+    }
+
+    #[test]
+    fn present_values_reverse_when_descending() {
+        assert_eq!(sorted_names(SortColumn::Name, true), ["c", "b", "a"]);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::models::{ScheduleTrigger, ScheduledSearch, StoreManager};
+use crate::scraping::{CompletedRun, Scheduler, WebScraper};
+use crate::utils::FileManager;
+use chrono::{DateTime, Local, Utc};
+use eframe::egui;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Como mucho se espera esto entre comprobaciones aunque no toque ninguna ejecución
+const MAX_WAKEUP: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TriggerKind {
+    Interval,
+    Cron,
+}
+
+pub struct SchedulesTab {
+    scheduler: Scheduler,
+    schedules: Vec<ScheduledSearch>,
+    file_manager: FileManager,
+    name: String,
+    query: String,
+    stores: BTreeSet<String>,
+    trigger_kind: TriggerKind,
+    interval_minutes: u64,
+    cron_expression: String,
+    form_error: Option<String>,
+}
+
+impl SchedulesTab {
+    pub fn new(scraper: Arc<WebScraper>) -> Self {
+        let file_manager = FileManager::new();
+        Self {
+            scheduler: Scheduler::new(scraper),
+            schedules: file_manager.load_app_config().schedules,
+            file_manager,
+            name: String::new(),
+            query: String::new(),
+            stores: BTreeSet::new(),
+            trigger_kind: TriggerKind::Interval,
+            interval_minutes: 60,
+            cron_expression: "0 9 * * *".to_string(),
+            form_error: None,
+        }
+    }
+
+    /// Lanza las programaciones vencidas y recoge las terminadas. Se llama en cada
+    /// frame, esté o no visible la pestaña.
+    pub fn poll(&mut self, ctx: &egui::Context, store_manager: &StoreManager) -> Vec<CompletedRun> {
+        let now = Utc::now();
+        let repaint = ctx.clone();
+        self.scheduler
+            .start_due(&self.schedules, store_manager, now, move || repaint.request_repaint());
+
+        let completed = self.scheduler.poll();
+
+        if let Some(next) = self.scheduler.next_wakeup(&self.schedules, Utc::now()) {
+            let wait = (next - Utc::now()).to_std().unwrap_or_default();
+            ctx.request_repaint_after(wait.min(MAX_WAKEUP));
+        }
+
+        completed
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, store_manager: &StoreManager) {
+        ui.heading("⏰ Búsquedas Programadas");
+        ui.separator();
+        ui.label("Se ejecutan mientras la aplicación está abierta, o con `yam-webs-cli daemon`.");
+        ui.add_space(5.0);
+
+        let now = Utc::now();
+        let mut changed = false;
+        let mut remove = None;
+        let mut run_now = None;
+
+        egui::ScrollArea::vertical()
+            .id_source("schedules")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                if self.schedules.is_empty() {
+                    ui.label("No hay búsquedas programadas. Añade una abajo.");
+                    return;
+                }
+
+                egui::Grid::new("schedules_grid").striped(true).show(ui, |ui| {
+                    ui.strong("Nombre");
+                    ui.strong("Búsqueda");
+                    ui.strong("Tiendas");
+                    ui.strong("Frecuencia");
+                    ui.strong("Última ejecución");
+                    ui.strong("Próxima");
+                    ui.strong("");
+                    ui.end_row();
+
+                    for (i, schedule) in self.schedules.iter_mut().enumerate() {
+                        changed |= ui.checkbox(&mut schedule.enabled, &schedule.name).changed();
+                        ui.label(&schedule.query);
+                        if schedule.stores.is_empty() {
+                            ui.label("Habilitadas");
+                        } else {
+                            ui.add(egui::Label::new(schedule.stores.join(", ")).truncate(true));
+                        }
+                        ui.label(schedule.trigger.describe());
+
+                        if self.scheduler.is_running(&schedule.name) {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label("En curso");
+                            });
+                        } else {
+                            match self.scheduler.last_run(&schedule.name) {
+                                Some(run) if run.succeeded() => {
+                                    ui.label(format!("✅ {} · {} productos", local_time(run.finished_at), run.product_count));
+                                }
+                                Some(run) => {
+                                    ui.colored_label(
+                                        egui::Color32::YELLOW,
+                                        format!(
+                                            "⚠️ {} · {} productos, {} errores",
+                                            local_time(run.finished_at),
+                                            run.product_count,
+                                            run.errors.len()
+                                        ),
+                                    )
+                                    .on_hover_text(run.errors.join("\n"));
+                                }
+                                None => {
+                                    ui.label("Nunca");
+                                }
+                            }
+                        }
+
+                        match self.scheduler.next_run(schedule, now) {
+                            _ if !schedule.enabled => ui.label("Pausada"),
+                            Ok(next) if next <= now => ui.label("Ahora"),
+                            Ok(next) => ui.label(local_time(next)),
+                            Err(e) => ui.colored_label(egui::Color32::RED, format!("❌ {}", e)),
+                        };
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(!self.scheduler.is_running(&schedule.name), egui::Button::new("▶ Ahora"))
+                                .clicked()
+                            {
+                                run_now = Some(i);
+                            }
+                            if ui.small_button("❌").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some(i) = run_now {
+            let repaint = ui.ctx().clone();
+            self.scheduler
+                .run_now(&self.schedules[i], store_manager, move || repaint.request_repaint());
+        }
+        if let Some(i) = remove {
+            let removed = self.schedules.remove(i);
+            self.scheduler.forget(&removed.name);
+            changed = true;
+        }
+
+        ui.add_space(10.0);
+        ui.collapsing("➕ Nueva búsqueda programada", |ui| {
+            if self.show_schedule_form(ui, store_manager) {
+                changed = true;
+            }
+        });
+
+        if changed {
+            self.save_schedules();
+        }
+    }
+
+    /// Formulario de alta; devuelve true si se añadió una programación
+    fn show_schedule_form(&mut self, ui: &mut egui::Ui, store_manager: &StoreManager) -> bool {
+        ui.horizontal(|ui| {
+            ui.label("Nombre:");
+            ui.text_edit_singleline(&mut self.name);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Búsqueda:");
+            ui.text_edit_singleline(&mut self.query);
+        });
+
+        let stores_label = if self.stores.is_empty() {
+            "Tiendas: las habilitadas".to_string()
+        } else {
+            format!("Tiendas: {}", self.stores.len())
+        };
+        ui.menu_button(stores_label, |ui| {
+            for store in &store_manager.stores {
+                let mut checked = self.stores.contains(&store.name);
+                if ui.checkbox(&mut checked, &store.name).changed() {
+                    if checked {
+                        self.stores.insert(store.name.clone());
+                    } else {
+                        self.stores.remove(&store.name);
+                    }
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.trigger_kind, TriggerKind::Interval, "Cada");
+            ui.add_enabled(
+                self.trigger_kind == TriggerKind::Interval,
+                egui::DragValue::new(&mut self.interval_minutes)
+                    .clamp_range(1..=10_080)
+                    .suffix(" min"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.trigger_kind, TriggerKind::Cron, "Cron:");
+            ui.add_enabled(
+                self.trigger_kind == TriggerKind::Cron,
+                egui::TextEdit::singleline(&mut self.cron_expression).hint_text("min hora día mes día-semana"),
+            );
+        });
+
+        let mut added = false;
+        if ui.button("Añadir").clicked() {
+            match self.build_schedule() {
+                Ok(schedule) => {
+                    self.schedules.push(schedule);
+                    self.name.clear();
+                    self.query.clear();
+                    self.stores.clear();
+                    self.form_error = None;
+                    added = true;
+                }
+                Err(e) => self.form_error = Some(e),
+            }
+        }
+
+        if let Some(error) = &self.form_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        added
+    }
+
+    fn build_schedule(&self) -> Result<ScheduledSearch, String> {
+        let trigger = match self.trigger_kind {
+            TriggerKind::Interval => ScheduleTrigger::Interval {
+                minutes: self.interval_minutes,
+            },
+            TriggerKind::Cron => ScheduleTrigger::Cron {
+                expression: self.cron_expression.trim().to_string(),
+            },
+        };
+
+        let name = match self.name.trim() {
+            "" => self.query.trim().to_string(),
+            name => name.to_string(),
+        };
+        if self.schedules.iter().any(|schedule| schedule.name.eq_ignore_ascii_case(&name)) {
+            return Err(format!("Ya existe una programación llamada \"{}\"", name));
+        }
+
+        let schedule = ScheduledSearch {
+            name,
+            query: self.query.trim().to_string(),
+            stores: self.stores.iter().cloned().collect(),
+            trigger,
+            enabled: true,
+        };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// Las programaciones viven en config.json junto al resto de AppConfig
+    fn save_schedules(&self) {
+        let mut config = self.file_manager.load_app_config();
+        config.schedules = self.schedules.clone();
+        if let Err(e) = self.file_manager.save_app_config(&config) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+fn local_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+impl Default for SchedulesTab {
+    fn default() -> Self {
+        Self::new(Arc::new(WebScraper::new()))
+    }
+}
@@ -1,25 +1,68 @@
-use crate::models::{Product, StoreManager};
-use crate::scraping::WebScraper;
+use crate::models::{Product, SearchHistory, SearchHistoryEntry, StoreManager};
+use crate::scraping::{SearchEngine, SearchEvent, SearchHandle, WebScraper};
+use crate::utils::FileManager;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Sugerencias que se muestran bajo el campo de búsqueda
+const MAX_SUGGESTIONS: usize = 8;
 
 pub struct SearchTab {
     search_term: String,
+    // Término de la búsqueda en curso o de la última terminada
+    last_query: String,
     is_searching: bool,
     search_status: String,
-    scraper: WebScraper,
+    engine: SearchEngine,
+    current_search: Option<SearchHandle>,
+    pending_products: Vec<Product>,
+    finished_stores: usize,
+    successful_searches: usize,
+    failed_searches: usize,
+    file_manager: FileManager,
+    history: SearchHistory,
+    // Tiendas y momento de inicio de la búsqueda en curso, para el historial
+    searched_stores: Vec<String>,
+    search_started: Option<Instant>,
+    // Búsqueda por lotes: archivo, consultas cargadas y progreso del lote en curso
+    batch_path: String,
+    batch_queries: Vec<String>,
+    batch_error: Option<String>,
+    batch_running: bool,
+    batch_finished_queries: usize,
 }
 
 impl SearchTab {
-    pub fn new() -> Self {
+    pub fn new(scraper: Arc<WebScraper>) -> Self {
+        let file_manager = FileManager::new();
+        let history = file_manager.load_search_history();
+
         Self {
-            search_term: String::new(),
+            search_term: history.last_query().unwrap_or_default().to_string(),
+            last_query: String::new(),
             is_searching: false,
             search_status: String::new(),
-            scraper: WebScraper::new(),
+            engine: SearchEngine::new(scraper),
+            current_search: None,
+            pending_products: Vec::new(),
+            finished_stores: 0,
+            successful_searches: 0,
+            failed_searches: 0,
+            file_manager,
+            history,
+            searched_stores: Vec::new(),
+            search_started: None,
+            batch_path: String::new(),
+            batch_queries: Vec::new(),
+            batch_error: None,
+            batch_running: false,
+            batch_finished_queries: 0,
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, store_manager: &mut StoreManager) -> Option<Vec<Product>> {
-        let mut search_results = None;
+        let search_results = self.poll_search();
 
         ui.heading("🔍 Búsqueda de Ofertas");
         ui.separator();
@@ -27,7 +70,8 @@ impl SearchTab {
         // Campo de búsqueda
         ui.horizontal(|ui| {
             ui.label("Término de búsqueda:");
-            ui.text_edit_singleline(&mut self.search_term);
+            let response = ui.text_edit_singleline(&mut self.search_term);
+            self.show_suggestions(ui, &response);
         });
 
         ui.add_space(10.0);
@@ -63,8 +107,7 @@ impl SearchTab {
             );
 
             if search_button.clicked() {
-                self.start_search();
-                search_results = self.perform_search(&self.search_term.clone(), store_manager);
+                self.start_search(ui.ctx(), store_manager);
             }
 
             if self.is_searching {
@@ -79,6 +122,12 @@ impl SearchTab {
             ui.label(&self.search_status);
         }
 
+        ui.add_space(15.0);
+        self.show_batch(ui, store_manager);
+
+        ui.add_space(15.0);
+        self.show_history(ui, store_manager);
+
         // Configuración avanzada de búsqueda
         ui.add_space(15.0);
         ui.collapsing("⚙️ Configuración Avanzada", |ui| {
@@ -97,40 +146,351 @@ impl SearchTab {
         search_results
     }
 
-    fn start_search(&mut self) {
+    /// Término con el que se obtuvieron los últimos resultados
+    pub fn last_query(&self) -> &str {
+        &self.last_query
+    }
+
+    /// Autocompletado con las consultas del historial
+    fn show_suggestions(&mut self, ui: &mut egui::Ui, response: &egui::Response) {
+        let popup_id = ui.make_persistent_id("search_suggestions");
+        let suggestions = self.history.suggestions(&self.search_term, MAX_SUGGESTIONS);
+
+        if suggestions.is_empty() {
+            if ui.memory(|memory| memory.is_popup_open(popup_id)) {
+                ui.memory_mut(|memory| memory.close_popup());
+            }
+        } else if response.has_focus() {
+            ui.memory_mut(|memory| memory.open_popup(popup_id));
+        }
+
+        egui::popup_below_widget(ui, popup_id, response, |ui| {
+            ui.set_min_width(200.0);
+            for suggestion in suggestions {
+                if ui.selectable_label(false, &suggestion).clicked() {
+                    self.search_term = suggestion;
+                }
+            }
+        });
+    }
+
+    /// Carga de una lista de consultas y lanzamiento del lote
+    fn show_batch(&mut self, ui: &mut egui::Ui, store_manager: &StoreManager) {
+        ui.collapsing("📋 Búsqueda por lotes", |ui| {
+            ui.label("Un archivo de texto con una consulta por línea, o un CSV con las consultas en la primera columna.");
+            ui.horizontal(|ui| {
+                ui.label("Archivo:");
+                ui.text_edit_singleline(&mut self.batch_path);
+                if ui
+                    .add_enabled(!self.batch_path.trim().is_empty(), egui::Button::new("Cargar"))
+                    .clicked()
+                {
+                    match self.file_manager.load_query_list(self.batch_path.trim()) {
+                        Ok(queries) => {
+                            self.batch_queries = queries;
+                            self.batch_error = None;
+                        }
+                        Err(e) => {
+                            self.batch_queries.clear();
+                            self.batch_error = Some(e);
+                        }
+                    }
+                }
+            });
+
+            if let Some(error) = &self.batch_error {
+                ui.colored_label(egui::Color32::RED, error);
+            } else if !self.batch_queries.is_empty() {
+                ui.label(format!("{} consultas cargadas", self.batch_queries.len()))
+                    .on_hover_text(self.batch_queries.join("\n"));
+            }
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        !self.batch_queries.is_empty() && !self.is_searching,
+                        egui::Button::new("▶ Buscar lote"),
+                    )
+                    .clicked()
+                {
+                    self.start_batch(ui.ctx(), store_manager);
+                }
+
+                if self.batch_running {
+                    ui.spinner();
+                    ui.label(format!(
+                        "{}/{} consultas",
+                        self.batch_finished_queries,
+                        self.batch_queries.len()
+                    ));
+                    if ui.button("⏹ Cancelar").clicked() {
+                        self.cancel_batch();
+                    }
+                }
+            });
+        });
+    }
+
+    /// Lista del historial con acciones para repetir, fijar y borrar
+    fn show_history(&mut self, ui: &mut egui::Ui, store_manager: &mut StoreManager) {
+        ui.collapsing(format!("🕘 Historial ({})", self.history.entries.len()), |ui| {
+            if self.history.entries.is_empty() {
+                ui.label("Todavía no hay búsquedas.");
+                return;
+            }
+
+            // Las fijadas primero, sin perder el orden cronológico dentro de cada grupo
+            let mut order: Vec<usize> = (0..self.history.entries.len()).collect();
+            order.sort_by_key(|&i| !self.history.entries[i].pinned);
+
+            let mut rerun = None;
+            let mut toggle_pin = None;
+            let mut remove = None;
+
+            egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                egui::Grid::new("search_history").striped(true).show(ui, |ui| {
+                    ui.strong("");
+                    ui.strong("Búsqueda");
+                    ui.strong("Tiendas");
+                    ui.strong("Fecha");
+                    ui.strong("Resultados");
+                    ui.strong("Duración");
+                    ui.strong("");
+                    ui.end_row();
+
+                    for i in order {
+                        let entry = &self.history.entries[i];
+                        let pin = if entry.pinned { "📌" } else { "📍" };
+                        if ui
+                            .selectable_label(entry.pinned, pin)
+                            .on_hover_text(if entry.pinned { "Desfijar" } else { "Fijar" })
+                            .clicked()
+                        {
+                            toggle_pin = Some(i);
+                        }
+                        ui.label(&entry.query);
+                        ui.add(egui::Label::new(entry.stores.join(", ")).truncate(true));
+                        ui.label(
+                            entry
+                                .searched_at
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M")
+                                .to_string(),
+                        );
+                        ui.label(entry.result_count.to_string());
+                        ui.label(format!("{:.1} s", entry.duration_ms as f64 / 1000.0));
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(!self.is_searching, egui::Button::new("▶ Repetir"))
+                                .clicked()
+                            {
+                                rerun = Some(i);
+                            }
+                            if ui.button("🗑").on_hover_text("Borrar").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+
+            if let Some(i) = rerun {
+                self.rerun(ui.ctx(), store_manager, i);
+            }
+            if let Some(i) = toggle_pin {
+                self.history.toggle_pin(i);
+                self.save_history();
+            }
+            if let Some(i) = remove {
+                self.history.remove(i);
+                self.save_history();
+            }
+        });
+    }
+
+    /// Repite una búsqueda del historial con las mismas tiendas, si siguen existiendo
+    fn rerun(&mut self, ctx: &egui::Context, store_manager: &mut StoreManager, index: usize) {
+        let Some(entry) = self.history.entries.get(index) else {
+            return;
+        };
+
+        let known = store_manager
+            .stores
+            .iter()
+            .any(|store| entry.stores.contains(&store.name));
+        if known {
+            for store in &mut store_manager.stores {
+                store.enabled = entry.stores.contains(&store.name);
+            }
+        }
+
+        self.search_term = entry.query.clone();
+        self.start_search(ctx, store_manager);
+    }
+
+    fn save_history(&self) {
+        if let Err(e) = self.file_manager.save_search_history(&self.history) {
+            eprintln!("{}", e);
+        }
+    }
+
+    fn start_search(&mut self, ctx: &egui::Context, store_manager: &StoreManager) {
+        let stores: Vec<_> = store_manager
+            .get_enabled_stores()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        self.searched_stores = stores.iter().map(|store| store.name.clone()).collect();
+        self.search_started = Some(Instant::now());
         self.is_searching = true;
+        self.last_query = self.search_term.clone();
+        self.pending_products.clear();
+        self.finished_stores = 0;
+        self.successful_searches = 0;
+        self.failed_searches = 0;
         self.search_status = "Iniciando búsqueda...".to_string();
+
+        let ctx = ctx.clone();
+        self.current_search = Some(self.engine.start_search(
+            &self.search_term,
+            stores,
+            move || ctx.request_repaint(),
+        ));
     }
 
-    fn perform_search(&mut self, query: &str, store_manager: &StoreManager) -> Option<Vec<Product>> {
-        let enabled_stores = store_manager.get_enabled_stores();
-        let mut all_products = Vec::new();
-        let mut successful_searches = 0;
-        let mut failed_searches = 0;
+    /// Busca cada consulta de la lista en las tiendas habilitadas, esperando entre
+    /// consultas el retardo configurado. Los lotes no pasan al historial de búsquedas.
+    fn start_batch(&mut self, ctx: &egui::Context, store_manager: &StoreManager) {
+        let stores: Vec<_> = store_manager
+            .get_enabled_stores()
+            .into_iter()
+            .cloned()
+            .collect();
+        let delay = Duration::from_millis(self.file_manager.load_app_config().request_delay_ms);
 
-        for store in enabled_stores {
-            self.search_status = format!("Buscando en {}...", store.name);
-            
-            match self.scraper.search_products(query, store) {
-                Ok(products) => {
-                    successful_searches += 1;
-                    all_products.extend(products);
+        self.is_searching = true;
+        self.batch_running = true;
+        self.batch_finished_queries = 0;
+        self.last_query = format!("Lote de {} consultas", self.batch_queries.len());
+        self.pending_products.clear();
+        self.finished_stores = 0;
+        self.successful_searches = 0;
+        self.failed_searches = 0;
+        self.search_status = "Iniciando búsqueda por lotes...".to_string();
+
+        let ctx = ctx.clone();
+        self.current_search = Some(self.engine.start_batch(
+            self.batch_queries.clone(),
+            stores,
+            delay,
+            move || ctx.request_repaint(),
+        ));
+    }
+
+    /// Al soltar el handle el lote se detiene tras la consulta en curso; lo ya
+    /// encontrado se descarta
+    fn cancel_batch(&mut self) {
+        self.current_search = None;
+        self.is_searching = false;
+        self.batch_running = false;
+        self.pending_products.clear();
+        self.search_status = format!(
+            "Búsqueda por lotes cancelada tras {} de {} consultas.",
+            self.batch_finished_queries,
+            self.batch_queries.len()
+        );
+    }
+
+    /// Procesa los eventos de la búsqueda en curso; devuelve los productos al terminar
+    fn poll_search(&mut self) -> Option<Vec<Product>> {
+        let handle = self.current_search.as_ref()?;
+        let total_stores = handle.total_stores();
+        let mut finished = false;
+
+        for event in handle.poll() {
+            match event {
+                SearchEvent::StoreStarted { store_name } => {
+                    self.search_status = format!(
+                        "Buscando en {}... ({}/{} tiendas completadas)",
+                        store_name, self.finished_stores, total_stores
+                    );
+                }
+                SearchEvent::StoreFinished { store_name, result } => {
+                    self.finished_stores += 1;
+                    match result {
+                        Ok(products) => {
+                            self.successful_searches += 1;
+                            self.pending_products.extend(products);
+                        }
+                        Err(e) => {
+                            self.failed_searches += 1;
+                            eprintln!("Error buscando en {}: {}", store_name, e);
+                        }
+                    }
+                    self.search_status = format!(
+                        "{}/{} tiendas completadas, {} productos encontrados...",
+                        self.finished_stores,
+                        total_stores,
+                        self.pending_products.len()
+                    );
                 }
-                Err(e) => {
-                    failed_searches += 1;
-                    eprintln!("Error buscando en {}: {}", store.name, e);
+                SearchEvent::QueryStarted { index, query } => {
+                    // El progreso por tiendas se cuenta de nuevo en cada consulta
+                    self.finished_stores = 0;
+                    self.search_status = format!(
+                        "Consulta {}/{}: \"{}\" ({} productos encontrados hasta ahora)",
+                        index + 1,
+                        self.batch_queries.len(),
+                        query,
+                        self.pending_products.len()
+                    );
                 }
+                SearchEvent::QueryFinished { index } => self.batch_finished_queries = index + 1,
+                SearchEvent::Finished => finished = true,
             }
         }
 
+        if !finished {
+            return None;
+        }
+
+        self.current_search = None;
         self.is_searching = false;
+
+        if self.batch_running {
+            self.batch_running = false;
+            self.search_status = format!(
+                "Lote completado: {} consultas, {} búsquedas exitosas, {} fallidas. {} productos encontrados.",
+                self.batch_finished_queries,
+                self.successful_searches,
+                self.failed_searches,
+                self.pending_products.len()
+            );
+            return (!self.pending_products.is_empty()).then(|| std::mem::take(&mut self.pending_products));
+        }
         self.search_status = format!(
             "Búsqueda completada: {} tiendas exitosas, {} fallidas. {} productos encontrados.",
-            successful_searches, failed_searches, all_products.len()
+            self.successful_searches, self.failed_searches, self.pending_products.len()
         );
 
-        if !all_products.is_empty() {
-            Some(all_products)
+        self.history.record(SearchHistoryEntry {
+            query: self.last_query.clone(),
+            stores: std::mem::take(&mut self.searched_stores),
+            searched_at: Utc::now(),
+            result_count: self.pending_products.len(),
+            duration_ms: self
+                .search_started
+                .take()
+                .map(|started| started.elapsed().as_millis() as u64)
+                .unwrap_or_default(),
+            pinned: false,
+        });
+        self.save_history();
+
+        if !self.pending_products.is_empty() {
+            Some(std::mem::take(&mut self.pending_products))
         } else {
             None
         }
@@ -139,6 +499,6 @@ impl SearchTab {
 
 impl Default for SearchTab {
     fn default() -> Self {
-        Self::new()
+        Self::new(Arc::new(WebScraper::new()))
     }
 }
\ No newline at end of file
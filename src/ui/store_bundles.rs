@@ -0,0 +1,290 @@
+use crate::models::{ConflictResolution, ImportConfirmation, StoreBundle, StoreConfig, StoreManager};
+use crate::utils::FileManager;
+use chrono::NaiveDate;
+use eframe::egui;
+use std::collections::HashSet;
+
+/// Paquete leído a la espera de que el usuario resuelva los conflictos
+struct PendingImport {
+    bundle: StoreBundle,
+    resolutions: Vec<ConflictResolution>,
+    // Confirmación, tienda a tienda, de robots.txt y de los ajustes de red del paquete
+    confirmations: Vec<ImportConfirmation>,
+}
+
+/// Ventana para exportar tiendas a un paquete e importar paquetes de otros
+pub struct StoreBundlesWindow {
+    open: bool,
+    file_manager: FileManager,
+    selected: HashSet<String>,
+    // Cabeceras, cookies y credenciales del proxy sólo se exportan si se pide
+    include_private: bool,
+    author: String,
+    version: String,
+    description: String,
+    last_verified: String,
+    export_path: String,
+    import_path: String,
+    pending: Option<PendingImport>,
+    message: String,
+}
+
+impl StoreBundlesWindow {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            file_manager: FileManager::new(),
+            selected: HashSet::new(),
+            include_private: false,
+            author: String::new(),
+            version: "1.0".to_string(),
+            description: String::new(),
+            last_verified: chrono::Local::now().date_naive().format("%Y-%m-%d").to_string(),
+            export_path: "tiendas.bundle.json".to_string(),
+            import_path: String::new(),
+            pending: None,
+            message: String::new(),
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, store_manager: &mut StoreManager) {
+        let mut open = self.open;
+
+        egui::Window::new("📦 Compartir tiendas")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                egui::CollapsingHeader::new("Exportar")
+                    .default_open(true)
+                    .show(ui, |ui| self.show_export(ui, store_manager));
+
+                egui::CollapsingHeader::new("Importar")
+                    .default_open(true)
+                    .show(ui, |ui| self.show_import(ui, store_manager));
+
+                if !self.message.is_empty() {
+                    ui.separator();
+                    ui.label(&self.message);
+                }
+            });
+
+        self.open = open;
+    }
+
+    fn show_export(&mut self, ui: &mut egui::Ui, store_manager: &StoreManager) {
+        ui.label("Tiendas a incluir:");
+        for store in &store_manager.stores {
+            let mut checked = self.selected.contains(&store.name);
+            if ui.checkbox(&mut checked, &store.name).changed() {
+                if checked {
+                    self.selected.insert(store.name.clone());
+                } else {
+                    self.selected.remove(&store.name);
+                }
+            }
+        }
+
+        egui::Grid::new("bundle_metadata").num_columns(2).show(ui, |ui| {
+            ui.label("Autor:");
+            ui.text_edit_singleline(&mut self.author);
+            ui.end_row();
+            ui.label("Versión:");
+            ui.text_edit_singleline(&mut self.version);
+            ui.end_row();
+            ui.label("Descripción:");
+            ui.text_edit_singleline(&mut self.description);
+            ui.end_row();
+            ui.label("Verificado el (AAAA-MM-DD):");
+            ui.text_edit_singleline(&mut self.last_verified);
+            ui.end_row();
+            ui.label("Guardar en:");
+            ui.text_edit_singleline(&mut self.export_path);
+            ui.end_row();
+        });
+
+        ui.checkbox(
+            &mut self.include_private,
+            "Incluir cabeceras, cookies y credenciales del proxy",
+        )
+        .on_hover_text("Pueden contener tokens o sesiones; no los compartas con quien no deba verlos");
+
+        let stores: Vec<_> = store_manager
+            .stores
+            .iter()
+            .filter(|store| self.selected.contains(&store.name))
+            .map(|store| {
+                if self.include_private {
+                    store.clone()
+                } else {
+                    store.without_private_settings()
+                }
+            })
+            .collect();
+
+        let can_export = !stores.is_empty() && !self.export_path.trim().is_empty();
+        if ui.add_enabled(can_export, egui::Button::new("Exportar paquete")).clicked() {
+            self.message = match self.build_bundle(stores) {
+                Ok(bundle) => match self.file_manager.export_bundle(&bundle, self.export_path.trim()) {
+                    Ok(_) => format!("✅ {} tiendas exportadas a {}", bundle.stores.len(), self.export_path.trim()),
+                    Err(e) => format!("❌ {}", e),
+                },
+                Err(e) => format!("❌ {}", e),
+            };
+        }
+    }
+
+    fn build_bundle(&self, stores: Vec<StoreConfig>) -> Result<StoreBundle, String> {
+        let last_verified = match self.last_verified.trim() {
+            "" => None,
+            date => Some(
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| format!("Fecha de verificación inválida: {}", date))?,
+            ),
+        };
+
+        let mut bundle = StoreBundle::new(stores);
+        bundle.author = self.author.trim().to_string();
+        bundle.version = self.version.trim().to_string();
+        bundle.description = self.description.trim().to_string();
+        bundle.last_verified = last_verified;
+        Ok(bundle)
+    }
+
+    fn show_import(&mut self, ui: &mut egui::Ui, store_manager: &mut StoreManager) {
+        ui.horizontal(|ui| {
+            ui.label("Archivo:");
+            ui.text_edit_singleline(&mut self.import_path);
+            if ui
+                .add_enabled(!self.import_path.trim().is_empty(), egui::Button::new("Abrir"))
+                .clicked()
+            {
+                match self.file_manager.load_bundle(self.import_path.trim()) {
+                    Ok(bundle) => {
+                        let resolutions = vec![ConflictResolution::Skip; bundle.stores.len()];
+                        let confirmations = vec![ImportConfirmation::default(); bundle.stores.len()];
+                        self.pending = Some(PendingImport {
+                            bundle,
+                            resolutions,
+                            confirmations,
+                        });
+                        self.message.clear();
+                    }
+                    Err(e) => self.message = format!("❌ {}", e),
+                }
+            }
+        });
+
+        let Some(pending) = &mut self.pending else {
+            return;
+        };
+
+        let bundle = &pending.bundle;
+        ui.group(|ui| {
+            if !bundle.description.is_empty() {
+                ui.label(&bundle.description);
+            }
+            ui.label(format!(
+                "Autor: {} · Versión: {} · Verificado: {}",
+                if bundle.author.is_empty() { "-" } else { &bundle.author },
+                if bundle.version.is_empty() { "-" } else { &bundle.version },
+                bundle
+                    .last_verified
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            ));
+            ui.label(format!(
+                "Exportado el {}",
+                bundle.exported_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            ));
+        });
+
+        egui::Grid::new("bundle_conflicts").striped(true).show(ui, |ui| {
+            for (i, store) in bundle.stores.iter().enumerate() {
+                ui.label(&store.name);
+                ui.vertical(|ui| {
+                    if !store.is_valid() {
+                        ui.colored_label(egui::Color32::RED, "❌ Configuración inválida: no se importará");
+                        return;
+                    }
+
+                    if bundle.is_repeated(i) {
+                        // Al importar, la tienda anterior con el mismo nombre ya existirá
+                        let resolution = &mut pending.resolutions[i];
+                        ui.horizontal(|ui| {
+                            ui.label("⚠ Repetida en el paquete:");
+                            ui.radio_value(resolution, ConflictResolution::Skip, "Omitir");
+                            ui.radio_value(resolution, ConflictResolution::Rename, "Renombrar");
+                        });
+                    } else if store_manager.find_by_name(&store.name).is_none() {
+                        ui.label("Nueva");
+                    } else {
+                        let resolution = &mut pending.resolutions[i];
+                        ui.horizontal(|ui| {
+                            ui.label("Ya existe:");
+                            ui.radio_value(resolution, ConflictResolution::Skip, "Omitir");
+                            ui.radio_value(resolution, ConflictResolution::Overwrite, "Sobrescribir");
+                            ui.radio_value(
+                                resolution,
+                                ConflictResolution::Rename,
+                                format!("Renombrar a \"{}\"", store_manager.unique_name(&store.name)),
+                            );
+                        });
+                    }
+
+                    if bundle.ignores_robots(i) {
+                        ui.checkbox(
+                            &mut pending.confirmations[i].ignore_robots,
+                            "⚠ Ignora robots.txt: conservarlo (sólo con permiso del sitio)",
+                        );
+                    }
+
+                    let overrides = bundle.network_overrides(i);
+                    if !overrides.is_empty() {
+                        ui.checkbox(
+                            &mut pending.confirmations[i].network_overrides,
+                            format!("⚠ Trae sus propios ajustes de red ({}): conservarlos", overrides.join(", ")),
+                        )
+                        .on_hover_text(
+                            "Un proxy ajeno ve todo el tráfico de la tienda, incluidas sus cookies. \
+                             Sin marcar, la tienda usa los ajustes globales.",
+                        );
+                        if let Some(proxy) = &store.http.proxy {
+                            ui.label(format!("Proxy: {:?} {}", proxy.mode, proxy.url));
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+        let mut close = false;
+        ui.horizontal(|ui| {
+            if ui.button("Importar").clicked() {
+                let summary =
+                    store_manager.import_bundle(&pending.bundle, &pending.resolutions, &pending.confirmations);
+                self.message = format!(
+                    "✅ {} nuevas, {} sobrescritas, {} renombradas, {} omitidas, {} inválidas. Pulsa Guardar para conservar los cambios.",
+                    summary.added, summary.overwritten, summary.renamed, summary.skipped, summary.invalid
+                );
+                close = true;
+            }
+            if ui.button("Cancelar").clicked() {
+                close = true;
+            }
+        });
+
+        if close {
+            self.pending = None;
+        }
+    }
+}
+
+impl Default for StoreBundlesWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
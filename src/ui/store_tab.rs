@@ -1,6 +1,19 @@
-use crate::models::{StoreConfig, StoreManager};
+use crate::models::{
+    CustomField, CustomFieldKind, ExtractionMode, FieldSource, FieldSpec, PaginationMode, StoreConfig, StoreManager,
+    Transform,
+};
 use crate::scraping::selectors::SelectorHelper;
+use crate::scraping::{
+    ProxyConfig, ProxyMode, RateLimitConfig, RetryPolicy, ScrapeError, SelectorTestReport, WebScraper,
+};
+use crate::ui::store_bundles::StoreBundlesWindow;
+use crate::utils::FileManager;
 use eframe::egui;
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+
+type SelectorTestResult = Result<SelectorTestReport, ScrapeError>;
 
 pub struct StoreTab {
     new_store: StoreConfig,
@@ -8,24 +21,51 @@ pub struct StoreTab {
     editing: bool,
     selector_helper: SelectorHelper,
     test_url: String,
+    // La prueba reutiliza la página descargada mientras no venza la caché
+    test_use_cache: bool,
     selector_type: String,
     show_suggestions: bool,
+    scraper: Arc<WebScraper>,
+    running_test: Option<Receiver<SelectorTestResult>>,
+    test_result: Option<SelectorTestResult>,
+    bundles: StoreBundlesWindow,
+    // Proxy de config.json, para mostrar el que usa cada tienda sin uno propio
+    global_proxy: ProxyConfig,
+    // Nombre y valor en edición para añadir una cabecera o una cookie inicial
+    new_header: (String, String),
+    new_cookie: (String, String),
+    cookie_message: Option<String>,
+    // Resultado del último "Guardar"/"Actualizar": nombre repetido o error al guardar
+    save_message: Option<String>,
 }
 
 impl StoreTab {
-    pub fn new() -> Self {
+    pub fn new(scraper: Arc<WebScraper>) -> Self {
         Self {
             new_store: StoreConfig::default(),
             selected_store: None,
             editing: false,
             selector_helper: SelectorHelper::new(),
             test_url: String::new(),
+            test_use_cache: true,
             selector_type: "container".to_string(),
             show_suggestions: false,
+            scraper,
+            running_test: None,
+            test_result: None,
+            bundles: StoreBundlesWindow::new(),
+            global_proxy: FileManager::new().load_app_config().proxy,
+            new_header: (String::new(), String::new()),
+            new_cookie: (String::new(), String::new()),
+            cookie_message: None,
+            save_message: None,
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, store_manager: &mut StoreManager) {
+        self.poll_selector_test();
+        self.bundles.show(ui.ctx(), store_manager);
+
         ui.heading("🏪 Gestión de Tiendas");
         ui.separator();
 
@@ -43,17 +83,25 @@ impl StoreTab {
                     self.selected_store = None;
                 }
 
+                if ui.button("📦 Compartir tiendas").clicked() {
+                    self.bundles.open();
+                }
+
                 ui.separator();
 
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     for (i, store) in store_manager.stores.iter().enumerate() {
                         let is_selected = self.selected_store == Some(i);
-                        let response = ui.selectable_label(is_selected, &store.name);
+                        let proxy = store.http.proxy.as_ref().unwrap_or(&self.global_proxy);
+                        let response = ui
+                            .selectable_label(is_selected, &store.name)
+                            .on_hover_text(format!("{}\n{}", store.base_url, proxy.describe()));
 
                         if response.clicked() {
                             self.selected_store = Some(i);
                             self.new_store = store.clone();
                             self.editing = true;
+                            self.cookie_message = None;
                         }
                     }
                 });
@@ -87,11 +135,171 @@ impl StoreTab {
             ui.collapsing("Ayuda sobre patrones de URL", |ui| {
                 ui.label("Usa {base_url} para la URL base y {query} para el término de búsqueda.");
                 ui.label("Ejemplo: {base_url}/search?q={query}");
+                ui.label("Para paginar usa {page} (número de página) u {offset} (productos saltados).");
+                ui.label("Ejemplo: {base_url}/search?q={query}&page={page}");
+            });
+
+            ui.collapsing("Paginación", |ui| {
+                let pagination = &mut self.new_store.pagination;
+
+                egui::ComboBox::from_label("Modo")
+                    .selected_text(match pagination.mode {
+                        PaginationMode::None => "Sólo primera página",
+                        PaginationMode::UrlPattern => "Patrón de URL ({page}/{offset})",
+                        PaginationMode::NextLink => "Enlace \"siguiente\"",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut pagination.mode, PaginationMode::None, "Sólo primera página");
+                        ui.selectable_value(&mut pagination.mode, PaginationMode::UrlPattern, "Patrón de URL ({page}/{offset})");
+                        ui.selectable_value(&mut pagination.mode, PaginationMode::NextLink, "Enlace \"siguiente\"");
+                    });
+
+                if pagination.mode != PaginationMode::None {
+                    ui.horizontal(|ui| {
+                        ui.label("Máximo de páginas:");
+                        ui.add(egui::DragValue::new(&mut pagination.max_pages).clamp_range(1..=100));
+                    });
+                }
+
+                match pagination.mode {
+                    PaginationMode::UrlPattern => {
+                        ui.horizontal(|ui| {
+                            ui.label("Primera página:");
+                            ui.add(egui::DragValue::new(&mut pagination.first_page));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Productos por página:");
+                            ui.add(egui::DragValue::new(&mut pagination.page_size).clamp_range(1..=1000));
+                        });
+                    }
+                    PaginationMode::NextLink => {
+                        ui.horizontal(|ui| {
+                            ui.label("Selector del enlace siguiente:");
+                            ui.text_edit_singleline(&mut pagination.next_page_selector);
+                        });
+                    }
+                    PaginationMode::None => {}
+                }
             });
 
             ui.checkbox(&mut self.new_store.enabled, "Habilitada");
+            ui.checkbox(&mut self.new_store.ignore_robots, "Ignorar robots.txt (sólo con permiso escrito del sitio)");
+
+            // Ajustes HTTP propios de la tienda
+            ui.collapsing("Conexión HTTP (opcional)", |ui| {
+                ui.label("Deja un campo desmarcado para usar el valor global de config.json.");
+                let http = &mut self.new_store.http;
+
+                ui.horizontal(|ui| {
+                    let mut has_user_agent = http.user_agent.is_some();
+                    ui.checkbox(&mut has_user_agent, "User-Agent:");
+                    if has_user_agent {
+                        let user_agent = http.user_agent.get_or_insert_with(String::new);
+                        ui.text_edit_singleline(user_agent);
+                    } else {
+                        http.user_agent = None;
+                    }
+                });
+
+                optional_number(ui, "Delay entre requests (ms):", &mut http.request_delay_ms, 1000);
+                optional_number(ui, "Timeout de conexión (s):", &mut http.connect_timeout_secs, 10);
+                optional_number(ui, "Timeout de lectura (s):", &mut http.read_timeout_secs, 30);
+                optional_number(ui, "Máximo de redirecciones:", &mut http.max_redirects, 10);
+
+                let mut has_retry = http.retry.is_some();
+                ui.checkbox(&mut has_retry, "Política de reintentos propia");
+                if has_retry {
+                    let retry = http.retry.get_or_insert_with(RetryPolicy::default);
+                    ui.horizontal(|ui| {
+                        ui.label("Reintentos:");
+                        ui.add(egui::DragValue::new(&mut retry.max_retries).clamp_range(0..=10));
+                        ui.label("Espera base (ms):");
+                        ui.add(egui::DragValue::new(&mut retry.base_delay_ms));
+                        ui.label("Espera máxima (ms):");
+                        ui.add(egui::DragValue::new(&mut retry.max_delay_ms));
+                    });
+                } else {
+                    http.retry = None;
+                }
+
+                let mut has_rate_limit = http.rate_limit.is_some();
+                ui.checkbox(&mut has_rate_limit, "Límite de velocidad propio");
+                if has_rate_limit {
+                    let rate_limit = http.rate_limit.get_or_insert_with(RateLimitConfig::default);
+                    ui.horizontal(|ui| {
+                        ui.label("Peticiones por segundo:");
+                        ui.add(
+                            egui::DragValue::new(&mut rate_limit.requests_per_second)
+                                .speed(0.1)
+                                .clamp_range(0.0..=100.0),
+                        );
+                        ui.label("Ráfaga:");
+                        ui.add(egui::DragValue::new(&mut rate_limit.burst).clamp_range(1..=100));
+                        ui.label("Simultáneas:");
+                        ui.add(egui::DragValue::new(&mut rate_limit.max_in_flight).clamp_range(0..=32));
+                    });
+                    ui.label("0 peticiones por segundo o 0 simultáneas significa sin límite.");
+                } else {
+                    http.rate_limit = None;
+                }
+
+                let mut has_proxy = http.proxy.is_some();
+                ui.checkbox(&mut has_proxy, "Proxy propio");
+                if has_proxy {
+                    proxy_options(ui, http.proxy.get_or_insert_with(ProxyConfig::default));
+                } else {
+                    http.proxy = None;
+                    ui.label(format!("Se usa el global: {}", self.global_proxy.describe()));
+                }
+
+                ui.separator();
+                ui.label("Cabeceras fijas:");
+                key_value_editor(ui, "store_headers", &mut http.headers, &mut self.new_header, "Accept-Language");
+                ui.label("Cookies iniciales (sólo se ponen si el sitio no fijó otro valor):");
+                key_value_editor(ui, "store_cookies", &mut http.cookies, &mut self.new_cookie, "region");
+
+                if let Err(e) = http.validate() {
+                    ui.colored_label(egui::Color32::RED, e);
+                }
+            });
+
+            // El tarro va con el nombre guardado, no con el que se está editando
+            let saved_name = self
+                .selected_store
+                .filter(|_| self.editing)
+                .and_then(|i| store_manager.stores.get(i))
+                .map(|store| store.name.clone());
+            if let Some(store_name) = saved_name {
+                self.show_cookie_jar(ui, &store_name);
+            }
+
+            ui.collapsing("Formato de precio", |ui| {
+                let locale = &mut self.new_store.price_locale;
+                separator_combo(ui, "Separador decimal", &mut locale.decimal_separator);
+                separator_combo(ui, "Separador de miles", &mut locale.thousands_separator);
+
+                ui.horizontal(|ui| {
+                    let mut has_currency = locale.default_currency.is_some();
+                    ui.checkbox(&mut has_currency, "Moneda por defecto (ISO):");
+                    if has_currency {
+                        let currency = locale.default_currency.get_or_insert_with(String::new);
+                        ui.text_edit_singleline(currency);
+                    } else {
+                        locale.default_currency = None;
+                    }
+                });
+            });
 
             ui.separator();
+
+            egui::ComboBox::from_label("Modo de extracción")
+                .selected_text(extraction_mode_label(self.new_store.extraction_mode))
+                .show_ui(ui, |ui| {
+                    for mode in [ExtractionMode::CssWithFallback, ExtractionMode::Css, ExtractionMode::StructuredData] {
+                        ui.selectable_value(&mut self.new_store.extraction_mode, mode, extraction_mode_label(mode));
+                    }
+                });
+
             ui.heading("Selectores CSS");
 
             // Selectores - Modificado para evitar préstamos múltiples
@@ -111,46 +319,50 @@ impl StoreTab {
             // Nombre del Producto
             ui.horizontal(|ui| {
                 ui.label("Nombre del Producto:");
-                ui.text_edit_singleline(&mut self.new_store.name_selector);
+                ui.text_edit_singleline(&mut self.new_store.name_selector.selector);
                 
                 if ui.button("Sugerencias").clicked() {
                     self.show_suggestions = true;
                     self.selector_type = "title".to_string();
                 }
             });
+            field_spec_options(ui, "title", &mut self.new_store.name_selector);
             
             // Precio
             ui.horizontal(|ui| {
                 ui.label("Precio:");
-                ui.text_edit_singleline(&mut self.new_store.price_selector);
+                ui.text_edit_singleline(&mut self.new_store.price_selector.selector);
                 
                 if ui.button("Sugerencias").clicked() {
                     self.show_suggestions = true;
                     self.selector_type = "price".to_string();
                 }
             });
+            field_spec_options(ui, "price", &mut self.new_store.price_selector);
             
             // Imagen
             ui.horizontal(|ui| {
                 ui.label("Imagen:");
-                ui.text_edit_singleline(&mut self.new_store.image_selector);
+                ui.text_edit_singleline(&mut self.new_store.image_selector.selector);
                 
                 if ui.button("Sugerencias").clicked() {
                     self.show_suggestions = true;
                     self.selector_type = "image".to_string();
                 }
             });
+            field_spec_options(ui, "image", &mut self.new_store.image_selector);
             
             // Enlace
             ui.horizontal(|ui| {
                 ui.label("Enlace:");
-                ui.text_edit_singleline(&mut self.new_store.link_selector);
+                ui.text_edit_singleline(&mut self.new_store.link_selector.selector);
                 
                 if ui.button("Sugerencias").clicked() {
                     self.show_suggestions = true;
                     self.selector_type = "link".to_string();
                 }
             });
+            field_spec_options(ui, "link", &mut self.new_store.link_selector);
             
             // Selector de descripción (opcional)
             ui.horizontal(|ui| {
@@ -159,9 +371,8 @@ impl StoreTab {
                 ui.checkbox(&mut has_description, "");
                 
                 if has_description {
-                    let mut desc = self.new_store.description_selector.clone().unwrap_or_default();
-                    ui.text_edit_singleline(&mut desc);
-                    self.new_store.description_selector = Some(desc);
+                    let desc = self.new_store.description_selector.get_or_insert_with(FieldSpec::default);
+                    ui.text_edit_singleline(&mut desc.selector);
                     
                     if ui.button("Sugerencias").clicked() {
                         self.show_suggestions = true;
@@ -171,6 +382,47 @@ impl StoreTab {
                     self.new_store.description_selector = None;
                 }
             });
+            if let Some(desc) = &mut self.new_store.description_selector {
+                field_spec_options(ui, "description", desc);
+            }
+
+            ui.collapsing(format!("Campos personalizados ({})", self.new_store.custom_fields.len()), |ui| {
+                ui.label("Valoración, stock, envío, vendedor... se guardan en cada producto con este nombre.");
+
+                let mut remove = None;
+                for (i, field) in self.new_store.custom_fields.iter_mut().enumerate() {
+                    ui.group(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Nombre:");
+                            ui.add(egui::TextEdit::singleline(&mut field.name).desired_width(120.0));
+                            egui::ComboBox::from_id_source(("custom_field_kind", i))
+                                .selected_text(custom_field_kind_label(field.kind))
+                                .show_ui(ui, |ui| {
+                                    for kind in [CustomFieldKind::Text, CustomFieldKind::Number, CustomFieldKind::Flag] {
+                                        ui.selectable_value(&mut field.kind, kind, custom_field_kind_label(kind));
+                                    }
+                                });
+                            if ui.small_button("🗑").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Selector:");
+                            ui.text_edit_singleline(&mut field.spec.selector);
+                        });
+                        field_spec_options(ui, &format!("custom{}", i), &mut field.spec);
+                    });
+                }
+                if let Some(i) = remove {
+                    self.new_store.custom_fields.remove(i);
+                }
+
+                if ui.button("➕ Añadir campo").clicked() {
+                    self.new_store
+                        .custom_fields
+                        .push(CustomField::new("", CustomFieldKind::Text, FieldSpec::default()));
+                }
+            });
 
             // Herramienta de prueba de selectores
             ui.collapsing("Herramienta de prueba", |ui| {
@@ -179,11 +431,23 @@ impl StoreTab {
                     ui.text_edit_singleline(&mut self.test_url);
                 });
                 
-                if ui.button("Probar Selectores").clicked() {
-                    // Aquí iría la lógica para probar los selectores
-                    // Esto requeriría implementar una función que haga scraping
-                    // y muestre los resultados en tiempo real
-                }
+                ui.horizontal(|ui| {
+                    let can_test = !self.test_url.trim().is_empty() && self.running_test.is_none();
+                    if ui.add_enabled(can_test, egui::Button::new("Probar Selectores")).clicked() {
+                        self.start_selector_test(ui.ctx());
+                    }
+                    ui.checkbox(&mut self.test_use_cache, "Usar caché").on_hover_text(
+                        "Las pruebas repetidas usan la página ya descargada en lugar de volver a pedirla \
+                         a la tienda, hasta que venza la caché",
+                    );
+
+                    if self.running_test.is_some() {
+                        ui.spinner();
+                        ui.label("Probando...");
+                    }
+                });
+
+                self.show_selector_test_result(ui);
             });
 
             // Mostrar sugerencias de selectores
@@ -199,11 +463,16 @@ impl StoreTab {
                             if ui.button(&suggestion).clicked() {
                                 match self.selector_type.as_str() {
                                     "container" => self.new_store.product_container_selector = suggestion.clone(),
-                                    "title" => self.new_store.name_selector = suggestion.clone(),
-                                    "price" => self.new_store.price_selector = suggestion.clone(),
-                                    "image" => self.new_store.image_selector = suggestion.clone(),
-                                    "link" => self.new_store.link_selector = suggestion.clone(),
-                                    "description" => self.new_store.description_selector = Some(suggestion.clone()),
+                                    "title" => self.new_store.name_selector.selector = suggestion.clone(),
+                                    "price" => self.new_store.price_selector.selector = suggestion.clone(),
+                                    "image" => self.new_store.image_selector.selector = suggestion.clone(),
+                                    "link" => self.new_store.link_selector.selector = suggestion.clone(),
+                                    "description" => {
+                                        self.new_store
+                                            .description_selector
+                                            .get_or_insert_with(FieldSpec::default)
+                                            .selector = suggestion.clone()
+                                    }
                                     _ => {}
                                 }
                                 self.show_suggestions = false;
@@ -221,10 +490,21 @@ impl StoreTab {
             // Botones de acción
             ui.horizontal(|ui| {
                 if ui.button(if self.editing { "💾 Actualizar" } else { "💾 Guardar" }).clicked() {
-                    if self.new_store.is_valid() {
+                    let editing = if self.editing { self.selected_store } else { None };
+                    let repeated = store_manager
+                        .find_by_name(&self.new_store.name)
+                        .is_some_and(|other| Some(other) != editing);
+
+                    if repeated {
+                        self.save_message = Some(format!("Ya existe una tienda llamada {}", self.new_store.name));
+                    } else if self.new_store.is_valid() {
+                        self.save_message = None;
                         if self.editing {
-                            if let Some(idx) = self.selected_store {
-                                store_manager.stores[idx] = self.new_store.clone();
+                            if let Some(idx) = self.selected_store
+                                && let Err(e) = self.update_store(store_manager, idx)
+                            {
+                                self.save_message = Some(e);
+                                return;
                             }
                         } else {
                             store_manager.add_store(self.new_store.clone());
@@ -238,29 +518,453 @@ impl StoreTab {
                     }
                 }
 
-                if self.editing {
-                    if ui.button("🗑️ Eliminar").clicked() {
-                        if let Some(idx) = self.selected_store {
-                            store_manager.stores.remove(idx);
-                            self.new_store = StoreConfig::default();
-                            self.editing = false;
-                            self.selected_store = None;
-                        }
-                    }
+                if self.editing
+                    && ui.button("🗑️ Eliminar").clicked()
+                    && let Some(idx) = self.selected_store
+                {
+                    store_manager.stores.remove(idx);
+                    self.new_store = StoreConfig::default();
+                    self.editing = false;
+                    self.selected_store = None;
                 }
 
                 if ui.button("❌ Cancelar").clicked() {
                     self.new_store = StoreConfig::default();
                     self.editing = false;
                     self.selected_store = None;
+                    self.save_message = None;
+                }
+            });
+
+            if let Some(message) = &self.save_message {
+                ui.colored_label(egui::Color32::from_rgb(200, 120, 0), message);
+            }
+        });
+    }
+}
+
+impl StoreTab {
+    /// Sustituye la tienda por la que se está editando. Las cookies van por nombre: si
+    /// cambia, primero se guarda la lista de tiendas y sólo entonces se mueve el tarro, para
+    /// que stores.json y las cookies no queden con nombres distintos.
+    fn update_store(&mut self, store_manager: &mut StoreManager, idx: usize) -> Result<(), String> {
+        let old_name = store_manager.stores[idx].name.clone();
+        let previous = std::mem::replace(&mut store_manager.stores[idx], self.new_store.clone());
+        if old_name == self.new_store.name {
+            return Ok(());
+        }
+
+        if let Err(e) = FileManager::new().save_stores(store_manager) {
+            store_manager.stores[idx] = previous;
+            return Err(format!("No se renombró la tienda: {}", e));
+        }
+        if let Err(e) = self.scraper.rename_cookie_jar(&old_name, &self.new_store.name) {
+            // La tienda ya está guardada con el nombre nuevo; sólo se avisa
+            self.save_message = Some(format!("Tienda renombrada, pero sus cookies siguen como {}: {}", old_name, e));
+        }
+        Ok(())
+    }
+
+    /// Lanza la prueba en un hilo aparte con la configuración que se está editando
+    fn start_selector_test(&mut self, ctx: &egui::Context) {
+        let (sender, receiver) = mpsc::channel();
+        let scraper = Arc::clone(&self.scraper);
+        let store = self.new_store.clone();
+        let url = self.test_url.trim().to_string();
+        let use_cache = self.test_use_cache;
+        let ctx = ctx.clone();
+
+        std::thread::spawn(move || {
+            let result = scraper.test_selectors(&url, &store, use_cache);
+            if sender.send(result).is_ok() {
+                ctx.request_repaint();
+            }
+        });
+
+        self.running_test = Some(receiver);
+        self.test_result = None;
+    }
+
+    /// Cookies que el sitio ha ido fijando para la tienda, con opción de borrarlas
+    fn show_cookie_jar(&mut self, ui: &mut egui::Ui, store_name: &str) {
+        ui.collapsing("🍪 Cookies guardadas", |ui| {
+            let cookies = self.scraper.stored_cookies(store_name);
+            if cookies.is_empty() {
+                ui.label("Todavía no hay cookies para esta tienda.");
+            } else {
+                let mut remove = None;
+                egui::ScrollArea::vertical().id_source("cookie_jar").max_height(200.0).show(ui, |ui| {
+                    egui::Grid::new("cookie_jar_grid").striped(true).show(ui, |ui| {
+                        ui.strong("Dominio");
+                        ui.strong("Nombre");
+                        ui.strong("Valor");
+                        ui.strong("Caduca");
+                        ui.strong("");
+                        ui.end_row();
+
+                        for (i, cookie) in cookies.iter().enumerate() {
+                            ui.label(&cookie.domain);
+                            ui.label(&cookie.name);
+                            ui.add(egui::Label::new(&cookie.value).truncate(true))
+                                .on_hover_text(&cookie.value);
+                            match cookie.expires {
+                                Some(expires) => ui.label(
+                                    expires.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
+                                ),
+                                None => ui.label("Al cerrar sesión"),
+                            };
+                            if ui.small_button("❌").on_hover_text("Borrar").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                if let Some(i) = remove {
+                    self.cookie_message = self.scraper.remove_cookie(store_name, &cookies[i]).err();
+                }
+            }
+
+            if ui.button("🗑 Borrar todas").clicked() {
+                self.cookie_message = match self.scraper.clear_cookies(store_name) {
+                    Ok(()) => Some("Cookies borradas".to_string()),
+                    Err(e) => Some(e),
+                };
+            }
+            if let Some(message) = &self.cookie_message {
+                ui.label(message);
+            }
+        });
+    }
+
+    fn poll_selector_test(&mut self) {
+        let Some(receiver) = &self.running_test else {
+            return;
+        };
+
+        match receiver.try_recv() {
+            Ok(result) => {
+                self.test_result = Some(result);
+                self.running_test = None;
+            }
+            Err(mpsc::TryRecvError::Disconnected) => self.running_test = None,
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+    }
+
+    fn show_selector_test_result(&self, ui: &mut egui::Ui) {
+        let Some(result) = &self.test_result else {
+            return;
+        };
+
+        let report = match result {
+            Ok(report) => report,
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, format!("❌ {}", e));
+                return;
+            }
+        };
+
+        ui.separator();
+        ui.label("Coincidencias por selector:");
+        egui::Grid::new("selector_checks").striped(true).show(ui, |ui| {
+            ui.strong("Campo");
+            ui.strong("Selector");
+            ui.strong("Elementos");
+            ui.strong("Muestra");
+            ui.strong("Estado");
+            ui.end_row();
+
+            for check in &report.checks {
+                ui.label(&check.field);
+                ui.monospace(&check.selector);
+                ui.label(check.matches.to_string());
+                ui.label(check.sample.as_deref().unwrap_or(""));
+                match &check.error {
+                    Some(error) => ui.colored_label(egui::Color32::RED, format!("❌ {}", error)),
+                    None if check.selector.is_empty() => ui.label("—"),
+                    None => ui.colored_label(egui::Color32::GREEN, "✅"),
+                };
+                ui.end_row();
+            }
+        });
+
+        ui.add_space(5.0);
+        if report.used_structured_data {
+            ui.label("ℹ️ Los selectores no encontraron productos; se usaron datos estructurados.");
+        }
+        ui.label(format!("{} productos extraídos", report.products.len()));
+
+        if report.products.is_empty() {
+            return;
+        }
+
+        egui::ScrollArea::both().max_height(250.0).show(ui, |ui| {
+            egui::Grid::new("selector_test_products").striped(true).show(ui, |ui| {
+                ui.strong("Nombre");
+                ui.strong("Precio");
+                ui.strong("Enlace");
+                ui.strong("Imagen");
+                ui.strong("Descripción");
+                ui.strong("Campos");
+                ui.end_row();
+
+                for product in &report.products {
+                    ui.label(&product.name);
+                    ui.label(product.price.to_string());
+                    ui.label(&product.url);
+                    ui.label(&product.image_url);
+                    ui.label(product.description.as_deref().unwrap_or(""));
+                    let extra: Vec<String> = product
+                        .extra
+                        .iter()
+                        .map(|(key, value)| format!("{}: {}", key, value))
+                        .collect();
+                    ui.label(extra.join(" · "));
+                    ui.end_row();
                 }
             });
         });
     }
 }
 
+fn extraction_mode_label(mode: ExtractionMode) -> &'static str {
+    match mode {
+        ExtractionMode::Css => "Sólo selectores CSS",
+        ExtractionMode::StructuredData => "Sólo datos estructurados (JSON-LD / microdata)",
+        ExtractionMode::CssWithFallback => "CSS con respaldo de datos estructurados",
+    }
+}
+
+/// Opciones avanzadas de un campo: origen del valor, expresión regular y transformaciones
+fn field_spec_options(ui: &mut egui::Ui, id: &str, spec: &mut FieldSpec) {
+    egui::CollapsingHeader::new("Opciones de extracción")
+        .id_source(("field_spec", id))
+        .show(ui, |ui| {
+            let source_label = |source: &FieldSource| match source {
+                FieldSource::Text => "Texto",
+                FieldSource::OwnText => "Texto propio (sin hijos)",
+                FieldSource::Attribute(_) => "Atributo",
+                FieldSource::InnerHtml => "HTML interno",
+            };
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source(("field_source", id))
+                    .selected_text(source_label(&spec.source))
+                    .show_ui(ui, |ui| {
+                        for source in [
+                            FieldSource::Text,
+                            FieldSource::OwnText,
+                            FieldSource::Attribute(String::new()),
+                            FieldSource::InnerHtml,
+                        ] {
+                            let selected = std::mem::discriminant(&spec.source) == std::mem::discriminant(&source);
+                            if ui.selectable_label(selected, source_label(&source)).clicked() && !selected {
+                                spec.source = source;
+                            }
+                        }
+                    });
+
+                if let FieldSource::Attribute(name) = &mut spec.source {
+                    ui.label("Atributo:");
+                    ui.add(egui::TextEdit::singleline(name).hint_text("data-src").desired_width(100.0));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                let mut has_regex = spec.regex.is_some();
+                ui.checkbox(&mut has_regex, "Expresión regular:");
+                if has_regex {
+                    ui.text_edit_singleline(spec.regex.get_or_insert_with(String::new));
+                    ui.label("Grupo:");
+                    ui.add(egui::DragValue::new(&mut spec.capture_group).clamp_range(0..=9));
+                } else {
+                    spec.regex = None;
+                }
+            });
+            if let Err(e) = spec.validate() {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+
+            ui.label("Transformaciones (en orden):");
+            let mut remove = None;
+            for (i, transform) in spec.transforms.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    match transform {
+                        Transform::Trim => {
+                            ui.label("Recortar espacios");
+                        }
+                        Transform::CollapseWhitespace => {
+                            ui.label("Unificar espacios");
+                        }
+                        Transform::Replace { from, to } => {
+                            ui.label("Reemplazar");
+                            ui.add(egui::TextEdit::singleline(from).desired_width(80.0));
+                            ui.label("por");
+                            ui.add(egui::TextEdit::singleline(to).desired_width(80.0));
+                        }
+                        Transform::Prefix(prefix) => {
+                            ui.label("Prefijo");
+                            ui.add(egui::TextEdit::singleline(prefix).desired_width(120.0));
+                        }
+                        Transform::Suffix(suffix) => {
+                            ui.label("Sufijo");
+                            ui.add(egui::TextEdit::singleline(suffix).desired_width(120.0));
+                        }
+                    }
+                    if ui.small_button("🗑").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                spec.transforms.remove(i);
+            }
+
+            ui.menu_button("➕ Añadir transformación", |ui| {
+                let options = [
+                    ("Recortar espacios", Transform::Trim),
+                    ("Unificar espacios", Transform::CollapseWhitespace),
+                    ("Reemplazar", Transform::Replace { from: String::new(), to: String::new() }),
+                    ("Prefijo", Transform::Prefix(String::new())),
+                    ("Sufijo", Transform::Suffix(String::new())),
+                ];
+                for (label, transform) in options {
+                    if ui.button(label).clicked() {
+                        spec.transforms.push(transform);
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+}
+
+fn custom_field_kind_label(kind: CustomFieldKind) -> &'static str {
+    match kind {
+        CustomFieldKind::Text => "Texto",
+        CustomFieldKind::Number => "Número",
+        CustomFieldKind::Flag => "Sí/No (si existe)",
+    }
+}
+
+/// Selector de separador numérico; "Automático" deja que el parser lo deduzca
+fn separator_combo(ui: &mut egui::Ui, label: &str, value: &mut Option<char>) {
+    let text = |separator: Option<char>| match separator {
+        None => "Automático".to_string(),
+        Some(' ') => "Espacio".to_string(),
+        Some(c) => format!("\"{}\"", c),
+    };
+
+    egui::ComboBox::from_label(label)
+        .selected_text(text(*value))
+        .show_ui(ui, |ui| {
+            for option in [None, Some('.'), Some(','), Some(' '), Some('\'')] {
+                ui.selectable_value(value, option, text(option));
+            }
+        });
+}
+
+/// Campo numérico opcional: desmarcado significa "usar el valor global"
+fn optional_number<T: egui::emath::Numeric>(ui: &mut egui::Ui, label: &str, value: &mut Option<T>, default: T) {
+    ui.horizontal(|ui| {
+        let mut enabled = value.is_some();
+        ui.checkbox(&mut enabled, label);
+        if enabled {
+            ui.add(egui::DragValue::new(value.get_or_insert(default)));
+        } else {
+            *value = None;
+        }
+    });
+}
+
+/// Lista editable de pares nombre/valor con una fila para añadir uno nuevo
+fn key_value_editor(
+    ui: &mut egui::Ui,
+    id: &str,
+    entries: &mut BTreeMap<String, String>,
+    new_entry: &mut (String, String),
+    name_hint: &str,
+) {
+    let mut remove = None;
+    egui::Grid::new(id).num_columns(3).show(ui, |ui| {
+        for (name, value) in entries.iter_mut() {
+            ui.label(name.as_str());
+            ui.text_edit_singleline(value);
+            if ui.small_button("❌").clicked() {
+                remove = Some(name.clone());
+            }
+            ui.end_row();
+        }
+
+        ui.add(egui::TextEdit::singleline(&mut new_entry.0).hint_text(name_hint).desired_width(140.0));
+        ui.add(egui::TextEdit::singleline(&mut new_entry.1).hint_text("valor"));
+        if ui
+            .add_enabled(!new_entry.0.trim().is_empty(), egui::Button::new("➕"))
+            .clicked()
+        {
+            let (name, value) = std::mem::take(new_entry);
+            entries.insert(name.trim().to_string(), value);
+        }
+        ui.end_row();
+    });
+
+    if let Some(name) = remove {
+        entries.remove(&name);
+    }
+}
+
+/// Editor de proxy: modo, URL, credenciales y excepciones
+fn proxy_options(ui: &mut egui::Ui, proxy: &mut ProxyConfig) {
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut proxy.mode, ProxyMode::System, "Del sistema");
+        ui.radio_value(&mut proxy.mode, ProxyMode::Direct, "Directo (sin proxy)");
+        ui.radio_value(&mut proxy.mode, ProxyMode::Manual, "Manual");
+    });
+
+    if proxy.mode != ProxyMode::Manual {
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("URL:");
+        ui.add(egui::TextEdit::singleline(&mut proxy.url).hint_text("http://proxy.empresa.com:8080 o socks5://..."));
+    });
+    ui.horizontal(|ui| {
+        ui.label("Usuario:");
+        ui.add(egui::TextEdit::singleline(proxy.username.get_or_insert_with(String::new)).desired_width(120.0));
+        ui.label("Contraseña:");
+        ui.add(
+            egui::TextEdit::singleline(proxy.password.get_or_insert_with(String::new))
+                .password(true)
+                .desired_width(120.0),
+        );
+    });
+    if proxy.username.as_deref() == Some("") {
+        proxy.username = None;
+    }
+    if proxy.password.as_deref() == Some("") {
+        proxy.password = None;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Sin proxy para:");
+        let mut no_proxy = proxy.no_proxy.join(",");
+        if ui
+            .add(egui::TextEdit::singleline(&mut no_proxy).hint_text("localhost, .intranet.local"))
+            .changed()
+        {
+            proxy.no_proxy = no_proxy.split(',').map(str::to_string).collect();
+        }
+    });
+
+    if let Err(e) = proxy.validate() {
+        ui.colored_label(egui::Color32::RED, e);
+    }
+}
+
 impl Default for StoreTab {
     fn default() -> Self {
-        Self::new()
+        Self::new(Arc::new(WebScraper::new()))
     }
 }
\ No newline at end of file
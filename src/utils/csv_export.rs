@@ -0,0 +1,275 @@
+use crate::models::Product;
+use chrono::SecondsFormat;
+use encoding_rs::{EncoderResult, WINDOWS_1252};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+/// Columnas que se pueden exportar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CsvColumn {
+    Name,
+    RawPrice,
+    Amount,
+    Currency,
+    Url,
+    ImageUrl,
+    Store,
+    // Consulta que encontró el producto, útil en búsquedas por lotes
+    Query,
+    Description,
+    ScrapedAt,
+}
+
+impl CsvColumn {
+    pub const ALL: [CsvColumn; 10] = [
+        CsvColumn::Name,
+        CsvColumn::RawPrice,
+        CsvColumn::Amount,
+        CsvColumn::Currency,
+        CsvColumn::Url,
+        CsvColumn::ImageUrl,
+        CsvColumn::Store,
+        CsvColumn::Query,
+        CsvColumn::Description,
+        CsvColumn::ScrapedAt,
+    ];
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Name => "Nombre",
+            CsvColumn::RawPrice => "Precio",
+            CsvColumn::Amount => "Monto",
+            CsvColumn::Currency => "Moneda",
+            CsvColumn::Url => "URL",
+            CsvColumn::ImageUrl => "Imagen",
+            CsvColumn::Store => "Tienda",
+            CsvColumn::Query => "Consulta",
+            CsvColumn::Description => "Descripción",
+            CsvColumn::ScrapedAt => "Fecha",
+        }
+    }
+
+    fn value(&self, product: &Product) -> String {
+        match self {
+            CsvColumn::Name => product.name.clone(),
+            CsvColumn::RawPrice => product.price.raw.clone(),
+            CsvColumn::Amount => product.price.amount.map(|a| a.to_string()).unwrap_or_default(),
+            CsvColumn::Currency => product.price.currency.clone().unwrap_or_default(),
+            CsvColumn::Url => product.url.clone(),
+            CsvColumn::ImageUrl => product.image_url.clone(),
+            CsvColumn::Store => product.store_name.clone(),
+            CsvColumn::Query => product.query.clone().unwrap_or_default(),
+            CsvColumn::Description => product.description.clone().unwrap_or_default(),
+            CsvColumn::ScrapedAt => product
+                .scraped_at
+                .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CsvEncoding {
+    #[default]
+    Utf8,
+    /// UTF-8 con BOM, para que Excel reconozca las tildes
+    Utf8Bom,
+    /// Windows-1252; los caracteres que no existen se escriben como '?'
+    Windows1252,
+}
+
+/// Opciones de exportación CSV; se recuerdan en config.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub encoding: CsvEncoding,
+    pub columns: Vec<CsvColumn>,
+    // Añade una columna por cada campo personalizado presente en los productos
+    pub extra_fields: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            encoding: CsvEncoding::Utf8,
+            columns: CsvColumn::ALL.to_vec(),
+            extra_fields: true,
+        }
+    }
+}
+
+/// Genera el CSV (RFC 4180: campos entre comillas cuando hace falta y fin de línea CRLF)
+/// ya codificado según las opciones
+pub fn products_to_csv(products: &[Product], options: &CsvOptions) -> Vec<u8> {
+    let mut content = String::new();
+
+    let extra_keys: BTreeSet<&str> = if options.extra_fields {
+        products.iter().flat_map(|p| p.extra.keys().map(String::as_str)).collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    let header: Vec<Cow<str>> = options
+        .columns
+        .iter()
+        .map(|column| column.header())
+        .chain(extra_keys.iter().copied())
+        .map(|header| escape_field(header, options.delimiter))
+        .collect();
+    push_record(&mut content, &header, options.delimiter);
+
+    for product in products {
+        let values: Vec<String> = options
+            .columns
+            .iter()
+            .map(|column| column.value(product))
+            .chain(
+                extra_keys
+                    .iter()
+                    .map(|key| product.extra.get(*key).map(|v| v.to_string()).unwrap_or_default()),
+            )
+            .collect();
+        let record: Vec<Cow<str>> = values
+            .iter()
+            .map(|value| escape_field(value, options.delimiter))
+            .collect();
+        push_record(&mut content, &record, options.delimiter);
+    }
+
+    encode(&content, options.encoding)
+}
+
+fn push_record(content: &mut String, fields: &[Cow<str>], delimiter: char) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            content.push(delimiter);
+        }
+        content.push_str(field);
+    }
+    content.push_str("\r\n");
+}
+
+/// Entrecomilla el campo si contiene el delimitador, comillas o saltos de línea
+fn escape_field(field: &str, delimiter: char) -> Cow<'_, str> {
+    let needs_quotes = field.contains(delimiter)
+        || field.contains(['"', '\r', '\n'])
+        || field.starts_with(' ')
+        || field.ends_with(' ');
+
+    if needs_quotes {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+fn encode(content: &str, encoding: CsvEncoding) -> Vec<u8> {
+    match encoding {
+        CsvEncoding::Utf8 => content.as_bytes().to_vec(),
+        CsvEncoding::Utf8Bom => {
+            let mut bytes = vec![0xEF, 0xBB, 0xBF];
+            bytes.extend_from_slice(content.as_bytes());
+            bytes
+        }
+        CsvEncoding::Windows1252 => encode_windows_1252(content),
+    }
+}
+
+/// encoding_rs reemplaza lo que no puede codificar por entidades HTML; aquí se usa '?'
+fn encode_windows_1252(content: &str) -> Vec<u8> {
+    let mut encoder = WINDOWS_1252.new_encoder();
+    let mut output = Vec::with_capacity(content.len());
+    let mut buffer = [0u8; 1024];
+    let mut remaining = content;
+
+    loop {
+        let (result, read, written) = encoder.encode_from_utf8_without_replacement(remaining, &mut buffer, true);
+        output.extend_from_slice(&buffer[..written]);
+        remaining = &remaining[read..];
+
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(_) => output.push(b'?'),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExtraValue, Price, PriceLocale};
+
+    fn product(name: &str, price: &str) -> Product {
+        let mut product = Product::new(
+            name.to_string(),
+            Price::parse(price, &PriceLocale::default()),
+            "https://tienda.example/p/1".to_string(),
+            String::new(),
+            "Tienda".to_string(),
+        );
+        product.scraped_at = None;
+        product
+    }
+
+    fn options(columns: &[CsvColumn]) -> CsvOptions {
+        CsvOptions {
+            columns: columns.to_vec(),
+            ..CsvOptions::default()
+        }
+    }
+
+    fn csv(products: &[Product], options: &CsvOptions) -> String {
+        String::from_utf8(products_to_csv(products, options)).unwrap()
+    }
+
+    #[test]
+    fn quotes_fields_that_need_it() {
+        let products = [product("Taladro \"Pro\", 600W", "1.299,99 €")];
+        let content = csv(&products, &options(&[CsvColumn::Name, CsvColumn::Amount, CsvColumn::Currency]));
+        assert_eq!(content, "Nombre,Monto,Moneda\r\n\"Taladro \"\"Pro\"\", 600W\",1299.99,EUR\r\n");
+    }
+
+    #[test]
+    fn custom_delimiter_changes_what_is_quoted() {
+        let options = CsvOptions {
+            delimiter: ';',
+            ..options(&[CsvColumn::Name, CsvColumn::RawPrice])
+        };
+        let content = csv(&[product("Taladro, 600W", "1.299,99")], &options);
+        assert_eq!(content, "Nombre;Precio\r\nTaladro, 600W;1.299,99\r\n");
+    }
+
+    #[test]
+    fn extra_fields_become_columns() {
+        let mut with_stock = product("Taladro", "10");
+        with_stock.extra.insert("Stock".to_string(), ExtraValue::Flag(true));
+        let without_stock = product("Lijadora", "20");
+
+        let content = csv(&[with_stock.clone(), without_stock], &options(&[CsvColumn::Name]));
+        assert_eq!(content, "Nombre,Stock\r\nTaladro,Sí\r\nLijadora,\r\n");
+
+        let no_extras = CsvOptions {
+            extra_fields: false,
+            ..options(&[CsvColumn::Name])
+        };
+        assert_eq!(csv(&[with_stock], &no_extras), "Nombre\r\nTaladro\r\n");
+    }
+
+    #[test]
+    fn encodings() {
+        let products = [product("Cañería 日本", "10")];
+        let columns = options(&[CsvColumn::Name]);
+
+        let bom = products_to_csv(&products, &CsvOptions { encoding: CsvEncoding::Utf8Bom, ..columns.clone() });
+        assert!(bom.starts_with(&[0xEF, 0xBB, 0xBF]));
+
+        let latin = products_to_csv(&products, &CsvOptions { encoding: CsvEncoding::Windows1252, ..columns });
+        assert_eq!(latin, b"Nombre\r\nCa\xf1er\xeda ??\r\n");
+    }
+}
@@ -1,5 +1,12 @@
-use crate::models::{Product, StoreManager};
+use crate::models::{
+    Product, ScheduleRun, ScheduledSearch, SearchHistory, StoreBundle, StoreManager, Watchlist, BUNDLE_FORMAT_VERSION,
+};
+use crate::scraping::{CacheConfig, ProxyConfig, RateLimitConfig, RetryPolicy, RobotsPolicy};
+use crate::utils::csv_export::{self, CsvOptions};
+use crate::utils::query_list;
 use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -9,6 +16,14 @@ impl FileManager {
     const STORES_FILE: &'static str = "stores.json";
     const RESULTS_FILE: &'static str = "search_results.json";
     const CONFIG_FILE: &'static str = "config.json";
+    const WATCHLIST_FILE: &'static str = "watchlist.json";
+    const SEARCH_HISTORY_FILE: &'static str = "search_history.json";
+    const SCHEDULE_STATE_FILE: &'static str = "schedule_state.json";
+    pub const SNAPSHOTS_DIR: &'static str = "snapshots";
+    pub const HTTP_CACHE_DIR: &'static str = "cache/http";
+    pub const IMAGE_CACHE_DIR: &'static str = "cache/images";
+    pub const COOKIES_DIR: &'static str = "cookies";
+    pub const HISTORY_DB: &'static str = "price_history.db";
 
     pub fn new() -> Self {
         Self
@@ -35,7 +50,9 @@ impl FileManager {
         } else {
             // Crear archivo con configuración por defecto
             let default_stores = StoreManager::default();
-            self.save_stores(&default_stores);
+            if let Err(e) = self.save_stores(&default_stores) {
+                eprintln!("{}", e);
+            }
             default_stores
         }
     }
@@ -53,6 +70,121 @@ impl FileManager {
         }
     }
 
+    /// Escribe un paquete de tiendas para compartir
+    pub fn export_bundle(&self, bundle: &StoreBundle, filename: &str) -> Result<(), String> {
+        match serde_json::to_string_pretty(bundle) {
+            Ok(json) => {
+                match fs::write(filename, json) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(format!("Error al escribir el paquete: {}", e)),
+                }
+            }
+            Err(e) => Err(format!("Error al serializar el paquete: {}", e)),
+        }
+    }
+
+    /// Lee un paquete de tiendas, rechazando formatos más nuevos que esta versión
+    pub fn load_bundle(&self, filename: &str) -> Result<StoreBundle, String> {
+        let content = fs::read_to_string(filename).map_err(|e| format!("Error al leer el paquete: {}", e))?;
+        let bundle: StoreBundle =
+            serde_json::from_str(&content).map_err(|e| format!("El archivo no es un paquete de tiendas válido: {}", e))?;
+
+        if bundle.format_version > BUNDLE_FORMAT_VERSION {
+            return Err(format!(
+                "El paquete usa el formato {} y esta versión sólo entiende hasta el {}",
+                bundle.format_version, BUNDLE_FORMAT_VERSION
+            ));
+        }
+        Ok(bundle)
+    }
+
+    /// Lee una lista de consultas para una búsqueda por lotes; los .csv se leen por
+    /// su primera columna
+    pub fn load_query_list(&self, filename: &str) -> Result<Vec<String>, String> {
+        let content = fs::read_to_string(filename).map_err(|e| format!("Error al leer la lista de consultas: {}", e))?;
+        let csv = Path::new(filename)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+
+        let queries = query_list::parse_query_list(&content, csv);
+        if queries.is_empty() {
+            return Err("La lista no contiene ninguna consulta".to_string());
+        }
+        Ok(queries)
+    }
+
+    /// Carga las reglas de alerta; sin archivo la lista está vacía
+    pub fn load_watchlist(&self) -> Watchlist {
+        if Path::new(Self::WATCHLIST_FILE).exists() {
+            match fs::read_to_string(Self::WATCHLIST_FILE) {
+                Ok(content) => {
+                    match serde_json::from_str::<Watchlist>(&content) {
+                        Ok(watchlist) => watchlist,
+                        Err(e) => {
+                            eprintln!("Error al parsear watchlist.json: {}", e);
+                            Watchlist::default()
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error al leer watchlist.json: {}", e);
+                    Watchlist::default()
+                }
+            }
+        } else {
+            Watchlist::default()
+        }
+    }
+
+    /// Guarda las reglas de alerta
+    pub fn save_watchlist(&self, watchlist: &Watchlist) -> Result<(), String> {
+        match serde_json::to_string_pretty(watchlist) {
+            Ok(json) => {
+                match fs::write(Self::WATCHLIST_FILE, json) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(format!("Error al escribir watchlist.json: {}", e)),
+                }
+            }
+            Err(e) => Err(format!("Error al serializar alertas: {}", e)),
+        }
+    }
+
+    /// Carga el historial de búsquedas
+    pub fn load_search_history(&self) -> SearchHistory {
+        if Path::new(Self::SEARCH_HISTORY_FILE).exists() {
+            match fs::read_to_string(Self::SEARCH_HISTORY_FILE) {
+                Ok(content) => {
+                    match serde_json::from_str::<SearchHistory>(&content) {
+                        Ok(history) => history,
+                        Err(e) => {
+                            eprintln!("Error al parsear search_history.json: {}", e);
+                            SearchHistory::default()
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error al leer search_history.json: {}", e);
+                    SearchHistory::default()
+                }
+            }
+        } else {
+            SearchHistory::default()
+        }
+    }
+
+    /// Guarda el historial de búsquedas
+    pub fn save_search_history(&self, history: &SearchHistory) -> Result<(), String> {
+        match serde_json::to_string_pretty(history) {
+            Ok(json) => {
+                match fs::write(Self::SEARCH_HISTORY_FILE, json) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(format!("Error al escribir search_history.json: {}", e)),
+                }
+            }
+            Err(e) => Err(format!("Error al serializar el historial de búsquedas: {}", e)),
+        }
+    }
+
     /// Guarda los resultados de búsqueda
     pub fn save_search_results(&self, products: &[Product]) -> Result<(), String> {
         let results = SearchResults {
@@ -71,6 +203,67 @@ impl FileManager {
         }
     }
 
+    /// Guarda los productos de una ejecución programada en
+    /// snapshots/<programación>/<fecha>.json y devuelve la ruta
+    pub fn save_snapshot(
+        &self,
+        schedule_name: &str,
+        products: &[Product],
+        taken_at: DateTime<Utc>,
+    ) -> Result<String, String> {
+        let dir = Path::new(Self::SNAPSHOTS_DIR).join(safe_file_name(schedule_name));
+        fs::create_dir_all(&dir).map_err(|e| format!("Error al crear {}: {}", dir.display(), e))?;
+
+        let path = dir.join(format!("{}.json", taken_at.format("%Y%m%dT%H%M%SZ")));
+        let results = SearchResults {
+            timestamp: taken_at.to_rfc3339(),
+            products: products.to_vec(),
+        };
+
+        match serde_json::to_string_pretty(&results) {
+            Ok(json) => match fs::write(&path, json) {
+                Ok(_) => Ok(path.display().to_string()),
+                Err(e) => Err(format!("Error al escribir {}: {}", path.display(), e)),
+            },
+            Err(e) => Err(format!("Error al serializar la instantánea: {}", e)),
+        }
+    }
+
+    /// Última ejecución de cada búsqueda programada, por nombre
+    pub fn load_schedule_state(&self) -> BTreeMap<String, ScheduleRun> {
+        if Path::new(Self::SCHEDULE_STATE_FILE).exists() {
+            match fs::read_to_string(Self::SCHEDULE_STATE_FILE) {
+                Ok(content) => {
+                    match serde_json::from_str::<BTreeMap<String, ScheduleRun>>(&content) {
+                        Ok(state) => state,
+                        Err(e) => {
+                            eprintln!("Error al parsear schedule_state.json: {}", e);
+                            BTreeMap::new()
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error al leer schedule_state.json: {}", e);
+                    BTreeMap::new()
+                }
+            }
+        } else {
+            BTreeMap::new()
+        }
+    }
+
+    pub fn save_schedule_state(&self, state: &BTreeMap<String, ScheduleRun>) -> Result<(), String> {
+        match serde_json::to_string_pretty(state) {
+            Ok(json) => {
+                match fs::write(Self::SCHEDULE_STATE_FILE, json) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(format!("Error al escribir schedule_state.json: {}", e)),
+                }
+            }
+            Err(e) => Err(format!("Error al serializar el estado de las programaciones: {}", e)),
+        }
+    }
+
     /// Carga los últimos resultados de búsqueda
     pub fn load_search_results(&self) -> Vec<Product> {
         if Path::new(Self::RESULTS_FILE).exists() {
@@ -88,60 +281,62 @@ impl FileManager {
         }
     }
 
-    /// Carga la configuración general de la aplicación
+    /// Carga la configuración general de la aplicación. Si config.json tiene errores se
+    /// avisa por consola y se usan los valores por defecto sin tocar el archivo.
     pub fn load_app_config(&self) -> AppConfig {
-        if Path::new(Self::CONFIG_FILE).exists() {
-            match fs::read_to_string(Self::CONFIG_FILE) {
-                Ok(content) => {
-                    match serde_json::from_str::<AppConfig>(&content) {
-                        Ok(config) => config,
-                        Err(_) => AppConfig::default(),
-                    }
+        self.try_load_app_config().unwrap_or_else(|e| {
+            eprintln!("{}; se usan los valores por defecto", e);
+            AppConfig::default()
+        })
+    }
+
+    /// Como `load_app_config`, pero devuelve el error si config.json no se puede leer
+    pub fn try_load_app_config(&self) -> Result<AppConfig, String> {
+        match read_app_config(Path::new(Self::CONFIG_FILE))? {
+            Some(config) => Ok(config),
+            None => {
+                let default_config = AppConfig::default();
+                if let Err(e) = self.save_app_config(&default_config) {
+                    eprintln!("{}", e);
                 }
-                Err(_) => AppConfig::default(),
+                Ok(default_config)
             }
-        } else {
-            let default_config = AppConfig::default();
-            self.save_app_config(&default_config);
-            default_config
         }
     }
 
-    /// Guarda la configuración general de la aplicación
+    /// Guarda la configuración general de la aplicación. No sobrescribe un config.json
+    /// con errores: se perderían los ajustes que el usuario estaba corrigiendo a mano.
     pub fn save_app_config(&self, config: &AppConfig) -> Result<(), String> {
-        match serde_json::to_string_pretty(config) {
-            Ok(json) => {
-                match fs::write(Self::CONFIG_FILE, json) {
-                    Ok(_) => Ok(()),
-                    Err(e) => Err(format!("Error al escribir config.json: {}", e)),
-                }
-            }
-            Err(e) => Err(format!("Error al serializar config: {}", e)),
-        }
+        write_app_config(Path::new(Self::CONFIG_FILE), config)
     }
 
-    /// Exporta productos a CSV
-    pub fn export_to_csv(&self, products: &[Product], filename: &str) -> Result<(), String> {
-        let mut csv_content = String::from("Nombre,Precio,URL,Tienda,Descripción\n");
-        
-        for product in products {
-            let description = product.description.as_deref().unwrap_or("");
-            csv_content.push_str(&format!(
-                "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\n",
-                product.name.replace('"', "\"\""),
-                product.price.replace('"', "\"\""),
-                product.url.replace('"', "\"\""),
-                product.store_name.replace('"', "\"\""),
-                description.replace('"', "\"\"")
-            ));
+    /// Exporta productos a CSV con el delimitador, la codificación y las columnas indicadas
+    pub fn export_to_csv(&self, products: &[Product], filename: &str, options: &CsvOptions) -> Result<(), String> {
+        match fs::write(filename, self.products_to_csv(products, options)) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("Error al exportar CSV: {}", e)),
         }
+    }
 
-        match fs::write(filename, csv_content) {
+    /// Exporta productos a JSON
+    pub fn export_to_json(&self, products: &[Product], filename: &str) -> Result<(), String> {
+        let json = self.products_to_json(products)?;
+        match fs::write(filename, json) {
             Ok(_) => Ok(()),
-            Err(e) => Err(format!("Error al exportar CSV: {}", e)),
+            Err(e) => Err(format!("Error al exportar JSON: {}", e)),
         }
     }
 
+    /// Serializa productos como JSON legible
+    pub fn products_to_json(&self, products: &[Product]) -> Result<String, String> {
+        serde_json::to_string_pretty(products).map_err(|e| format!("Error al serializar productos: {}", e))
+    }
+
+    /// Genera el contenido CSV de una lista de productos, ya codificado
+    pub fn products_to_csv(&self, products: &[Product], options: &CsvOptions) -> Vec<u8> {
+        csv_export::products_to_csv(products, options)
+    }
+
     /// Crea un backup de la configuración
     pub fn create_backup(&self) -> Result<(), String> {
         let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
@@ -170,13 +365,54 @@ struct SearchResults {
     products: Vec<Product>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub max_products_per_store: usize,
     pub request_delay_ms: u64,
     pub user_agent: String,
     pub auto_save_results: bool,
     pub theme: String,
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_timeout_secs: u64,
+    #[serde(default = "default_max_redirects")]
+    pub max_redirects: usize,
+    #[serde(default)]
+    pub robots_policy: RobotsPolicy,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    // Límite de velocidad por dominio, compartido por búsquedas, páginas e imágenes
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    // Proxy para todas las peticiones; cada tienda puede tener el suyo
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    // Tamaño máximo de la caché de imágenes; 0 la desactiva
+    #[serde(default = "default_image_cache_mb")]
+    pub image_cache_mb: u64,
+    #[serde(default)]
+    pub csv_export: CsvOptions,
+    #[serde(default)]
+    pub schedules: Vec<ScheduledSearch>,
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_read_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_redirects() -> usize {
+    10
+}
+
+fn default_image_cache_mb() -> u64 {
+    50
 }
 
 impl Default for AppConfig {
@@ -187,6 +423,111 @@ impl Default for AppConfig {
             user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36".to_string(),
             auto_save_results: true,
             theme: "dark".to_string(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            read_timeout_secs: default_read_timeout_secs(),
+            max_redirects: default_max_redirects(),
+            robots_policy: RobotsPolicy::default(),
+            cache: CacheConfig::default(),
+            retry: RetryPolicy::default(),
+            rate_limit: RateLimitConfig::default(),
+            proxy: ProxyConfig::default(),
+            image_cache_mb: default_image_cache_mb(),
+            csv_export: CsvOptions::default(),
+            schedules: Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Lee config.json; `None` si todavía no existe
+fn read_app_config(path: &Path) -> Result<Option<AppConfig>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Error al leer {}: {}", path.display(), e))?;
+    serde_json::from_str::<AppConfig>(&content)
+        .map(Some)
+        .map_err(|e| format!("{} tiene errores: {}", path.display(), e))
+}
+
+fn write_app_config(path: &Path, config: &AppConfig) -> Result<(), String> {
+    if let Err(e) = read_app_config(path) {
+        return Err(format!("{}. Corrígelo o bórralo antes de guardar cambios", e));
+    }
+
+    let json = serde_json::to_string_pretty(config).map_err(|e| format!("Error al serializar config: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Error al escribir {}: {}", path.display(), e))
+}
+
+/// Nombre de archivo seguro a partir de un nombre libre (tienda, programación). Se
+/// conservan letras, dígitos y '-'; el resto se escribe como "_XX" por cada byte, así dos
+/// nombres distintos nunca comparten archivo.
+pub fn safe_file_name(name: &str) -> String {
+    if name.is_empty() {
+        return "_".to_string();
+    }
+
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '-' {
+            escaped.push(c);
+        } else {
+            let mut bytes = [0u8; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                escaped.push_str(&format!("_{:02X}", byte));
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_config(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yam-webs-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("config.json")
+    }
+
+    #[test]
+    fn missing_config_reads_as_none() {
+        let path = temp_config("missing");
+        let _ = fs::remove_file(&path);
+        assert!(read_app_config(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn broken_config_is_reported_and_not_overwritten() {
+        let path = temp_config("broken");
+        fs::write(&path, "{ \"user_agent\": ").unwrap();
+
+        assert!(read_app_config(&path).is_err());
+        assert!(write_app_config(&path, &AppConfig::default()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"user_agent\": ");
+    }
+
+    #[test]
+    fn valid_config_round_trips() {
+        let path = temp_config("valid");
+        let _ = fs::remove_file(&path);
+        let config = AppConfig {
+            request_delay_ms: 1234,
+            ..AppConfig::default()
+        };
+
+        write_app_config(&path, &config).unwrap();
+        let loaded = read_app_config(&path).unwrap().unwrap();
+        assert_eq!(loaded.request_delay_ms, 1234);
+    }
+
+    #[test]
+    fn safe_file_names_do_not_collide() {
+        let names = ["Tienda AR", "Tienda_AR", "Tienda/AR", "Tienda_20AR", "", "_"];
+        let files: std::collections::HashSet<String> = names.iter().map(|name| safe_file_name(name)).collect();
+        assert_eq!(files.len(), names.len());
+        assert_eq!(safe_file_name("Cañería-1"), "Cañería-1");
+        assert_eq!(safe_file_name("../x"), "_2E_2E_2Fx");
+    }
+}
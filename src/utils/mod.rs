@@ -1,3 +1,9 @@
+pub mod csv_export;
 pub mod file_manager;
+pub mod price_history;
+pub mod query_list;
 
-pub use file_manager::FileManager;
\ No newline at end of file
+pub use csv_export::{CsvColumn, CsvEncoding, CsvOptions};
+pub use file_manager::{safe_file_name, AppConfig, FileManager};
+pub use price_history::{PriceHistory, PriceObservation};
+pub use query_list::parse_query_list;
\ No newline at end of file
@@ -0,0 +1,315 @@
+use crate::models::{Price, Product};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use rusqlite::{params, Connection, Row};
+use rust_decimal::Decimal;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Una observación del precio de un producto en una búsqueda
+#[derive(Debug, Clone)]
+pub struct PriceObservation {
+    pub observed_at: DateTime<Utc>,
+    pub query: String,
+    pub store_name: String,
+    pub product_name: String,
+    pub url: String,
+    pub price: Price,
+}
+
+/// Historial de precios guardado en una base SQLite
+pub struct PriceHistory {
+    conn: Connection,
+}
+
+impl PriceHistory {
+    /// Abre (o crea) la base de datos del historial
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("Error al abrir el historial: {}", e))?;
+        Self::init(conn)
+    }
+
+    /// Historial en memoria, sin archivo
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(|e| format!("Error al abrir el historial: {}", e))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS observations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                product_key TEXT NOT NULL,
+                observed_at TEXT NOT NULL,
+                query TEXT NOT NULL,
+                store_name TEXT NOT NULL,
+                product_name TEXT NOT NULL,
+                url TEXT NOT NULL,
+                price_raw TEXT NOT NULL,
+                amount TEXT,
+                currency TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_observations_product
+                ON observations (product_key, observed_at);
+            -- Las claves por GTIN no llevaban la tienda
+            UPDATE observations SET product_key = store_name || '::' || product_key
+                WHERE product_key LIKE 'gtin:%';",
+        )
+        .map_err(|e| format!("Error al crear el historial: {}", e))?;
+
+        Ok(Self { conn })
+    }
+
+    /// Añade una observación por producto, todas con la misma fecha. Los productos de
+    /// una búsqueda por lotes se guardan con su propia consulta
+    pub fn record(&mut self, query: &str, products: &[Product]) -> Result<usize, String> {
+        self.record_at(query, products, Utc::now())
+    }
+
+    /// Igual que `record` con una fecha concreta
+    pub fn record_at(&mut self, query: &str, products: &[Product], observed_at: DateTime<Utc>) -> Result<usize, String> {
+        let observed_at = format_timestamp(observed_at);
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Error al guardar en el historial: {}", e))?;
+
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    "INSERT INTO observations
+                        (product_key, observed_at, query, store_name, product_name, url, price_raw, amount, currency)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                )
+                .map_err(|e| format!("Error al guardar en el historial: {}", e))?;
+
+            for product in products {
+                stmt.execute(params![
+                    product_key(product),
+                    observed_at,
+                    product.query.as_deref().unwrap_or(query),
+                    product.store_name,
+                    product.name,
+                    product.url,
+                    product.price.raw,
+                    product.price.amount.map(|a| a.to_string()),
+                    product.price.currency,
+                ])
+                .map_err(|e| format!("Error al guardar en el historial: {}", e))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Error al guardar en el historial: {}", e))?;
+        Ok(products.len())
+    }
+
+    /// Todas las observaciones de un producto, de la más antigua a la más reciente
+    pub fn history_for(&self, product: &Product) -> Result<Vec<PriceObservation>, String> {
+        self.query_observations(
+            "SELECT observed_at, query, store_name, product_name, url, price_raw, amount, currency
+             FROM observations WHERE product_key = ?1 ORDER BY observed_at, id",
+            &product_key(product),
+            None,
+        )
+    }
+
+    /// Última observación del producto con un precio interpretable
+    pub fn latest_observation(&self, product: &Product) -> Result<Option<PriceObservation>, String> {
+        let mut observations = self.query_observations(
+            "SELECT observed_at, query, store_name, product_name, url, price_raw, amount, currency
+             FROM observations WHERE product_key = ?1 AND amount IS NOT NULL
+             ORDER BY observed_at DESC, id DESC LIMIT 1",
+            &product_key(product),
+            None,
+        )?;
+        Ok(observations.pop())
+    }
+
+    /// Precio más bajo registrado para el producto desde la fecha indicada
+    pub fn lowest_price_since(&self, product: &Product, since: DateTime<Utc>) -> Result<Option<PriceObservation>, String> {
+        let observations = self.query_observations(
+            "SELECT observed_at, query, store_name, product_name, url, price_raw, amount, currency
+             FROM observations
+             WHERE product_key = ?1 AND observed_at >= ?2 AND amount IS NOT NULL
+             ORDER BY observed_at, id",
+            &product_key(product),
+            Some(format_timestamp(since)),
+        )?;
+
+        // Si el mínimo se repite se queda la primera vez que se vio
+        Ok(observations
+            .into_iter()
+            .reduce(|lowest, obs| if obs.price.cmp_amount(&lowest.price).is_lt() { obs } else { lowest }))
+    }
+
+    /// Precio más bajo de los últimos `days` días
+    pub fn lowest_price_in_last_days(&self, product: &Product, days: i64) -> Result<Option<PriceObservation>, String> {
+        self.lowest_price_since(product, Utc::now() - Duration::days(days))
+    }
+
+    fn query_observations(
+        &self,
+        sql: &str,
+        key: &str,
+        since: Option<String>,
+    ) -> Result<Vec<PriceObservation>, String> {
+        let mut stmt = self
+            .conn
+            .prepare_cached(sql)
+            .map_err(|e| format!("Error al consultar el historial: {}", e))?;
+
+        let rows = match since {
+            Some(since) => stmt.query_map(params![key, since], observation_from_row),
+            None => stmt.query_map(params![key], observation_from_row),
+        }
+        .map_err(|e| format!("Error al consultar el historial: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Error al leer el historial: {}", e))
+    }
+}
+
+/// Identifica un producto entre búsquedas: su URL o, si no tiene enlace propio (la URL
+/// está vacía o es la de la página de resultados), su GTIN, su SKU o su nombre, siempre
+/// dentro de la tienda: el mismo GTIN en otra tienda tiene otro precio
+pub fn product_key(product: &Product) -> String {
+    let without_fragment = |url: &str| url.split('#').next().unwrap_or_default().trim().to_string();
+    let url = without_fragment(&product.url);
+    let linkless = url.is_empty() || product.page_url.as_deref().is_some_and(|page| without_fragment(page) == url);
+
+    if !linkless {
+        return url;
+    }
+
+    if let Some(gtin) = identifier(&product.gtin) {
+        format!("{}::gtin:{}", product.store_name, gtin)
+    } else if let Some(sku) = identifier(&product.sku) {
+        format!("{}::sku:{}", product.store_name, sku)
+    } else {
+        format!("{}::{}", product.store_name, product.name.trim().to_lowercase())
+    }
+}
+
+fn identifier(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+// Formato fijo para que las fechas se comparen bien como texto
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn observation_from_row(row: &Row) -> rusqlite::Result<PriceObservation> {
+    let observed_at: String = row.get(0)?;
+    let amount: Option<String> = row.get(6)?;
+
+    Ok(PriceObservation {
+        observed_at: DateTime::parse_from_rfc3339(&observed_at)
+            .map(|d| d.with_timezone(&Utc))
+            .unwrap_or_default(),
+        query: row.get(1)?,
+        store_name: row.get(2)?,
+        product_name: row.get(3)?,
+        url: row.get(4)?,
+        price: Price {
+            raw: row.get(5)?,
+            amount: amount.and_then(|a| Decimal::from_str(&a).ok()),
+            currency: row.get(7)?,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PriceLocale;
+
+    const PAGE: &str = "https://tienda.example/buscar?q=taladro";
+
+    fn product(name: &str, url: &str, price: &str) -> Product {
+        let mut product = Product::new(
+            name.to_string(),
+            Price::parse(price, &PriceLocale::default()),
+            url.to_string(),
+            String::new(),
+            "Tienda".to_string(),
+        );
+        product.page_url = Some(PAGE.to_string());
+        product
+    }
+
+    fn days_ago(days: i64) -> DateTime<Utc> {
+        Utc::now() - Duration::days(days)
+    }
+
+    #[test]
+    fn linkless_products_do_not_share_the_page_key() {
+        let a = product("Taladro", PAGE, "10");
+        let b = product("Amoladora", PAGE, "20");
+        assert_ne!(product_key(&a), product_key(&b));
+        assert_eq!(product_key(&a), "Tienda::taladro");
+
+        let mut with_gtin = product("Taladro", PAGE, "10");
+        with_gtin.gtin = Some("7791234567890".to_string());
+        assert_eq!(product_key(&with_gtin), "Tienda::gtin:7791234567890");
+    }
+
+    #[test]
+    fn same_gtin_in_another_store_has_its_own_history() {
+        let mut history = PriceHistory::open_in_memory().unwrap();
+        let mut here = product("Taladro", PAGE, "100");
+        here.gtin = Some("7791234567890".to_string());
+        let mut there = here.clone();
+        there.store_name = "Otra".to_string();
+        there.price = Price::parse("60", &PriceLocale::default());
+
+        history.record_at("taladro", &[there], days_ago(1)).unwrap();
+        assert!(history.latest_observation(&here).unwrap().is_none());
+    }
+
+    #[test]
+    fn linked_product_is_keyed_by_url_without_fragment() {
+        let p = product("Taladro", "https://tienda.example/p/1#opiniones", "10");
+        assert_eq!(product_key(&p), "https://tienda.example/p/1");
+    }
+
+    #[test]
+    fn history_is_kept_per_product() {
+        let mut history = PriceHistory::open_in_memory().unwrap();
+        let taladro = product("Taladro", "https://tienda.example/p/1", "100");
+        let amoladora = product("Amoladora", PAGE, "50");
+
+        history.record_at("taladro", &[taladro.clone(), amoladora.clone()], days_ago(3)).unwrap();
+        history
+            .record_at("taladro", &[product("Taladro", "https://tienda.example/p/1", "90")], days_ago(1))
+            .unwrap();
+
+        let observations = history.history_for(&taladro).unwrap();
+        let amounts: Vec<String> = observations
+            .iter()
+            .map(|o| o.price.amount.unwrap().to_string())
+            .collect();
+        assert_eq!(amounts, ["100", "90"]);
+        assert_eq!(history.history_for(&amoladora).unwrap().len(), 1);
+        assert_eq!(
+            history.latest_observation(&taladro).unwrap().unwrap().price.amount,
+            Some(Decimal::from(90))
+        );
+    }
+
+    #[test]
+    fn lowest_price_only_looks_inside_the_window() {
+        let mut history = PriceHistory::open_in_memory().unwrap();
+        let url = "https://tienda.example/p/1";
+        history.record_at("q", &[product("Taladro", url, "50")], days_ago(40)).unwrap();
+        history.record_at("q", &[product("Taladro", url, "80")], days_ago(10)).unwrap();
+        history.record_at("q", &[product("Taladro", url, "Consultar")], days_ago(5)).unwrap();
+        history.record_at("q", &[product("Taladro", url, "95")], days_ago(2)).unwrap();
+
+        let taladro = product("Taladro", url, "95");
+        let lowest = history.lowest_price_in_last_days(&taladro, 30).unwrap().unwrap();
+        assert_eq!(lowest.price.amount, Some(Decimal::from(80)));
+        let lowest = history.lowest_price_in_last_days(&taladro, 60).unwrap().unwrap();
+        assert_eq!(lowest.price.amount, Some(Decimal::from(50)));
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+// Cabeceras de columna que se ignoran en la primera fila de un CSV
+const HEADER_NAMES: [&str; 5] = ["query", "consulta", "búsqueda", "busqueda", "producto"];
+
+/// Lee una lista de consultas: una por línea, o la primera columna si es CSV. Se
+/// ignoran las líneas vacías, los comentarios con '#' y las repetidas.
+pub fn parse_query_list(content: &str, csv: bool) -> Vec<String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut seen = HashSet::new();
+
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    // El separador se decide una vez, con la primera línea útil
+    let delimiter = if csv { lines.first().and_then(|line| detect_delimiter(line)) } else { None };
+
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let query = if csv { first_csv_field(line, delimiter) } else { line.to_string() };
+            let query = query.trim().to_string();
+            if query.is_empty() || (csv && i == 0 && HEADER_NAMES.contains(&query.to_lowercase().as_str())) {
+                return None;
+            }
+            Some(query)
+        })
+        .filter(|query| seen.insert(query.to_lowercase()))
+        .collect()
+}
+
+/// Separador de columnas: tabulador, punto y coma o coma, en ese orden de preferencia
+/// (con punto y coma la coma suele ser decimal). Sin ninguno fuera de comillas el CSV
+/// tiene una sola columna.
+fn detect_delimiter(line: &str) -> Option<char> {
+    let mut in_quotes = false;
+    let mut found = Vec::new();
+    for c in line.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\t' | ';' | ',' if !in_quotes => found.push(c),
+            _ => {}
+        }
+    }
+    ['\t', ';', ','].into_iter().find(|delimiter| found.contains(delimiter))
+}
+
+/// Primer campo de una línea CSV, quitando comillas
+fn first_csv_field(line: &str, delimiter: Option<char>) -> String {
+    let Some(quoted) = line.strip_prefix('"') else {
+        return match delimiter {
+            Some(delimiter) => line.split(delimiter).next().unwrap_or_default().to_string(),
+            None => line.to_string(),
+        };
+    };
+
+    let mut field = String::new();
+    let mut chars = quoted.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => break,
+            c => field.push(c),
+        }
+    }
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_list_skips_comments_blanks_and_repeats() {
+        let content = "\u{feff}taladro\n\n# herramientas\n  lijadora  \nTaladro\n";
+        assert_eq!(parse_query_list(content, false), ["taladro", "lijadora"]);
+    }
+
+    #[test]
+    fn header_is_detected_after_leading_comments() {
+        let content = "# exportado de la hoja\n\nConsulta,Cantidad\ntaladro,2\n";
+        assert_eq!(parse_query_list(content, true), ["taladro"]);
+    }
+
+    #[test]
+    fn semicolon_csv_keeps_decimal_commas() {
+        let content = "consulta;cantidad\ntornillo 3,5 mm;100\n\"tuerca \"\"M6\"\"\";50\n";
+        assert_eq!(parse_query_list(content, true), ["tornillo 3,5 mm", "tuerca \"M6\""]);
+    }
+
+    #[test]
+    fn comma_csv_keeps_semicolons_inside_quotes() {
+        let content = "\"taladro; percutor\",2\nlijadora,1\n";
+        assert_eq!(parse_query_list(content, true), ["taladro; percutor", "lijadora"]);
+    }
+
+    #[test]
+    fn single_column_csv_is_not_split() {
+        let content = "producto\ntornillo 3,5 mm\n";
+        assert_eq!(parse_query_list(content, true), ["tornillo 3,5 mm"]);
+    }
+}